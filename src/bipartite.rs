@@ -0,0 +1,203 @@
+#![allow(clippy::needless_range_loop)]
+
+use cargo_snippet::snippet;
+
+#[snippet("bipartite_matching")]
+pub struct BipartiteMatching {
+    left: usize,
+    right: usize,
+    edges: Vec<Vec<usize>>,
+    match_left: Vec<Option<usize>>,
+    match_right: Vec<Option<usize>>,
+}
+
+#[snippet("bipartite_matching")]
+impl BipartiteMatching {
+    pub fn new(left: usize, right: usize) -> Self {
+        Self {
+            left,
+            right,
+            edges: vec![Vec::new(); left],
+            match_left: vec![None; left],
+            match_right: vec![None; right],
+        }
+    }
+
+    pub fn add_edge(&mut self, l: usize, r: usize) {
+        assert!(l < self.left);
+        assert!(r < self.right);
+        self.edges[l].push(r);
+    }
+
+    fn levels(&self) -> (Vec<usize>, bool) {
+        let mut levels = vec![usize::MAX; self.left];
+        let mut queue = std::collections::VecDeque::new();
+        for l in 0..self.left {
+            if self.match_left[l].is_none() {
+                levels[l] = 0;
+                queue.push_back(l);
+            }
+        }
+
+        let mut found = false;
+        while let Some(l) = queue.pop_front() {
+            for &r in &self.edges[l] {
+                match self.match_right[r] {
+                    None => found = true,
+                    Some(next) if levels[next] == usize::MAX => {
+                        levels[next] = levels[l] + 1;
+                        queue.push_back(next);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        (levels, found)
+    }
+
+    fn augment(&mut self, root: usize, levels: &[usize], checked: &mut [usize]) -> bool {
+        let mut path = vec![root];
+        while let Some(&l) = path.last() {
+            if checked[l] == self.edges[l].len() {
+                path.pop();
+                continue;
+            }
+
+            let r = self.edges[l][checked[l]];
+            checked[l] += 1;
+            match self.match_right[r] {
+                None => {
+                    for &l in &path {
+                        let r = self.edges[l][checked[l] - 1];
+                        self.match_left[l] = Some(r);
+                        self.match_right[r] = Some(l);
+                    }
+                    return true;
+                }
+                Some(next) if levels[next] == levels[l] + 1 => path.push(next),
+                _ => {}
+            }
+        }
+        false
+    }
+
+    pub fn max_matching(&mut self) -> usize {
+        loop {
+            let (levels, found) = self.levels();
+            if !found {
+                break;
+            }
+            let mut checked = vec![0; self.left];
+            for l in 0..self.left {
+                if self.match_left[l].is_none() {
+                    self.augment(l, &levels, &mut checked);
+                }
+            }
+        }
+        self.match_left.iter().flatten().count()
+    }
+
+    pub fn matching(&self) -> Vec<(usize, usize)> {
+        (0..self.left)
+            .filter_map(|l| self.match_left[l].map(|r| (l, r)))
+            .collect()
+    }
+
+    fn reachable(&self) -> (Vec<bool>, Vec<bool>) {
+        let mut visited_left = vec![false; self.left];
+        let mut visited_right = vec![false; self.right];
+        let mut stack: Vec<usize> = (0..self.left)
+            .filter(|&l| self.match_left[l].is_none())
+            .collect();
+        for &l in &stack {
+            visited_left[l] = true;
+        }
+
+        while let Some(l) = stack.pop() {
+            for &r in &self.edges[l] {
+                if visited_right[r] || self.match_left[l] == Some(r) {
+                    continue;
+                }
+                visited_right[r] = true;
+                if let Some(next) = self.match_right[r] {
+                    if !visited_left[next] {
+                        visited_left[next] = true;
+                        stack.push(next);
+                    }
+                }
+            }
+        }
+        (visited_left, visited_right)
+    }
+
+    pub fn min_vertex_cover(&mut self) -> (Vec<usize>, Vec<usize>) {
+        self.max_matching();
+        let (visited_left, visited_right) = self.reachable();
+        (
+            (0..self.left).filter(|&l| !visited_left[l]).collect(),
+            (0..self.right).filter(|&r| visited_right[r]).collect(),
+        )
+    }
+
+    pub fn max_independent_set(&mut self) -> (Vec<usize>, Vec<usize>) {
+        self.max_matching();
+        let (visited_left, visited_right) = self.reachable();
+        (
+            (0..self.left).filter(|&l| visited_left[l]).collect(),
+            (0..self.right).filter(|&r| !visited_right[r]).collect(),
+        )
+    }
+}
+
+#[test]
+fn test_bipartite_matching() {
+    let edges = [
+        (0, 0),
+        (0, 1),
+        (1, 0),
+        (2, 1),
+        (2, 2),
+        (3, 2),
+        (4, 2),
+        (4, 4),
+    ];
+    let mut matching = BipartiteMatching::new(5, 5);
+    for &(l, r) in &edges {
+        matching.add_edge(l, r);
+    }
+
+    assert_eq!(matching.max_matching(), 4);
+    let pairs = matching.matching();
+    assert_eq!(pairs.len(), 4);
+    for &(l, r) in &pairs {
+        assert!(edges.contains(&(l, r)));
+    }
+    let mut seen = [false; 5];
+    for &(_, r) in &pairs {
+        assert!(!seen[r]);
+        seen[r] = true;
+    }
+
+    let (cover_left, cover_right) = matching.min_vertex_cover();
+    assert_eq!(cover_left.len() + cover_right.len(), 4);
+    for &(l, r) in &edges {
+        assert!(cover_left.contains(&l) || cover_right.contains(&r));
+    }
+
+    let (set_left, set_right) = matching.max_independent_set();
+    assert_eq!(set_left.len() + set_right.len(), 6);
+    for &(l, r) in &edges {
+        assert!(!(set_left.contains(&l) && set_right.contains(&r)));
+    }
+
+    let n = 100000;
+    let mut matching = BipartiteMatching::new(n, n);
+    for l in 0..n {
+        if l + 1 < n {
+            matching.add_edge(l, l + 1);
+        }
+        matching.add_edge(l, l);
+    }
+    assert_eq!(matching.max_matching(), n);
+    assert!(matching.matching().iter().all(|&(l, r)| l == r));
+}
@@ -40,6 +40,48 @@ pub fn float_bound<F: Fn(f64) -> bool>(min: f64, max: f64, f: F) -> f64 {
     ok
 }
 
+#[snippet("parallel_bound")]
+pub fn parallel_bound<T, I, A, F>(
+    queries: usize,
+    min: isize,
+    max: isize,
+    init: I,
+    mut advance: A,
+    mut f: F,
+) -> Vec<isize>
+where
+    I: Fn() -> T,
+    A: FnMut(&mut T, isize),
+    F: FnMut(&mut T, usize, isize) -> bool,
+{
+    let mut ok = vec![min; queries];
+    let mut ng = vec![max; queries];
+    loop {
+        let mut mids: Vec<(isize, usize)> = (0..queries)
+            .filter(|&i| (ng[i] - ok[i]).abs() > 1)
+            .map(|i| ((ok[i] + ng[i]) / 2, i))
+            .collect();
+        if mids.is_empty() {
+            break ok;
+        }
+        mids.sort_unstable();
+
+        let mut state = init();
+        let mut time = min.min(max);
+        for (mid, i) in mids {
+            while time < mid {
+                time += 1;
+                advance(&mut state, time);
+            }
+            if f(&mut state, i, mid) {
+                ok[i] = mid;
+            } else {
+                ng[i] = mid;
+            }
+        }
+    }
+}
+
 #[test]
 fn test_bound() {
     use approx::*;
@@ -57,3 +99,37 @@ fn test_bound() {
     assert_relative_eq!(float_bound(-10.0, 2.0, |x| x <= 100.0), 2.0);
     assert_relative_eq!(float_bound(-10.0, 2.0, |x| x <= -100.0), -10.0);
 }
+
+#[test]
+fn test_parallel_bound() {
+    use crate::union_find::{PersistentUnionFind, UnionFind};
+
+    let edges = [(0, 1), (2, 3), (4, 5), (1, 2), (0, 3), (5, 6), (3, 6)];
+    let queries = [(0, 1), (0, 3), (1, 4), (4, 6), (6, 0), (7, 0)];
+    let never = edges.len() as isize + 1;
+
+    let answers = parallel_bound(
+        queries.len(),
+        never,
+        0,
+        || UnionFind::new(8),
+        |uf, time| {
+            let (a, b) = edges[time as usize - 1];
+            uf.merge(a, b);
+        },
+        |uf, i, _| {
+            let (a, b) = queries[i];
+            uf.same_root(a, b)
+        },
+    );
+    assert_eq!(answers, vec![1, 4, 7, 6, 7, never]);
+
+    let mut puf = PersistentUnionFind::new(8);
+    for &(a, b) in &edges {
+        puf.merge(a, b);
+    }
+    for (&(a, b), &answer) in queries.iter().zip(&answers) {
+        let expected = bound(never, 0, |t| puf.same_root_at(a, b, t as usize));
+        assert_eq!(answer, expected);
+    }
+}
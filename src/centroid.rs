@@ -0,0 +1,152 @@
+use crate::graph::Graph;
+use cargo_snippet::snippet;
+
+#[snippet(name = "centroid", include = "graph")]
+pub struct CentroidDecomposition {
+    pub root: usize,
+    pub parent: Vec<Option<usize>>,
+    pub level: Vec<usize>,
+    pub levels: Vec<Vec<Vec<usize>>>,
+}
+
+#[snippet("centroid")]
+impl CentroidDecomposition {
+    pub fn new(graph: &Graph) -> Self {
+        let nodes = graph.nodes;
+        let mut removed = vec![false; nodes];
+        let mut parent = vec![None; nodes];
+        let mut level = vec![0; nodes];
+        let mut levels: Vec<Vec<Vec<usize>>> = Vec::new();
+        let mut size = vec![0; nodes];
+        let mut tree_parent = vec![usize::MAX; nodes];
+        let mut root = 0;
+        let mut queue = std::collections::VecDeque::new();
+        if nodes > 0 {
+            queue.push_back((0, None, 0));
+        }
+
+        while let Some((entry, centroid_parent, depth)) = queue.pop_front() {
+            let mut component = vec![entry];
+            tree_parent[entry] = entry;
+            let mut i = 0;
+            while let Some(&node) = component.get(i) {
+                i += 1;
+                for edge in &graph.edges[node] {
+                    if !removed[edge.node_to] && edge.node_to != tree_parent[node] {
+                        tree_parent[edge.node_to] = node;
+                        component.push(edge.node_to);
+                    }
+                }
+            }
+
+            for &node in component.iter().rev() {
+                size[node] = 1 + graph.edges[node]
+                    .iter()
+                    .filter(|edge| !removed[edge.node_to] && edge.node_to != tree_parent[node])
+                    .map(|edge| size[edge.node_to])
+                    .sum::<usize>();
+            }
+
+            let total = component.len();
+            let mut centroid = entry;
+            loop {
+                let heavier = graph.edges[centroid].iter().find(|edge| {
+                    !removed[edge.node_to]
+                        && edge.node_to != tree_parent[centroid]
+                        && size[edge.node_to] * 2 > total
+                });
+                match heavier {
+                    Some(edge) => centroid = edge.node_to,
+                    None => break,
+                }
+            }
+
+            removed[centroid] = true;
+            parent[centroid] = centroid_parent;
+            level[centroid] = depth;
+            if centroid_parent.is_none() {
+                root = centroid;
+            }
+
+            let position = component.iter().position(|&node| node == centroid).unwrap();
+            component.swap(0, position);
+            if levels.len() <= depth {
+                levels.push(Vec::new());
+            }
+            levels[depth].push(component);
+
+            for edge in &graph.edges[centroid] {
+                if !removed[edge.node_to] {
+                    queue.push_back((edge.node_to, Some(centroid), depth + 1));
+                }
+            }
+        }
+
+        Self {
+            root,
+            parent,
+            level,
+            levels,
+        }
+    }
+
+    pub fn tree(&self) -> Graph {
+        let mut tree = Graph::new(self.parent.len());
+        for (node, &parent) in self.parent.iter().enumerate() {
+            if let Some(parent) = parent {
+                tree.edge_undirected(parent, node);
+            }
+        }
+        tree
+    }
+}
+
+#[test]
+fn test_centroid_decomposition() {
+    let mut graph = Graph::new(15);
+    for node in 1..15 {
+        graph.edge_undirected((node - 1) / 2, node);
+    }
+    let centroids = CentroidDecomposition::new(&graph);
+    assert_eq!(centroids.root, 0);
+    assert_eq!(centroids.parent[1], Some(0));
+    assert_eq!(centroids.parent[2], Some(0));
+    assert_eq!(centroids.levels.len(), 4);
+    assert_eq!(centroids.levels[0], vec![(0..15).collect::<Vec<_>>()]);
+    assert_eq!(centroids.levels[1].len(), 2);
+    assert_eq!(centroids.levels[2].len(), 4);
+    assert_eq!(centroids.levels[3].len(), 8);
+
+    let mut path = Graph::new(100);
+    for node in 1..100 {
+        path.edge_undirected(node - 1, node);
+    }
+    let centroids = CentroidDecomposition::new(&path);
+    assert!(centroids.root == 49 || centroids.root == 50);
+    assert!(centroids.level.iter().all(|&level| level < 7));
+
+    for (depth, components) in centroids.levels.iter().enumerate() {
+        let mut covered: Vec<usize> = components.iter().flatten().copied().collect();
+        covered.sort_unstable();
+        covered.dedup();
+        assert_eq!(
+            covered.len(),
+            components.iter().map(|c| c.len()).sum::<usize>()
+        );
+        for component in components {
+            let centroid = component[0];
+            assert_eq!(centroids.level[centroid], depth);
+            assert!(component.iter().all(|&node| centroids.level[node] >= depth));
+            let (min, max) = (
+                *component.iter().min().unwrap(),
+                *component.iter().max().unwrap(),
+            );
+            assert_eq!(max - min + 1, component.len());
+            assert!((centroid - min).max(max - centroid) <= component.len() / 2);
+        }
+    }
+
+    let tree = centroids.tree();
+    assert_eq!(tree.edge_count, 99);
+    assert_eq!(tree.bfs(centroids.root).iter().flatten().max(), Some(&6));
+}
@@ -0,0 +1,147 @@
+use cargo_snippet::snippet;
+
+#[snippet("csr")]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct CsrEdge<W> {
+    pub to: usize,
+    pub weight: W,
+    pub id: usize,
+}
+
+#[snippet("csr")]
+#[derive(Clone, Debug)]
+pub struct Csr<W> {
+    pub nodes: usize,
+    pub edge_count: usize,
+    start: Vec<usize>,
+    list: Vec<CsrEdge<W>>,
+}
+
+#[snippet("csr")]
+impl<W: Clone> Csr<W> {
+    pub fn directed(nodes: usize, edges: &[(usize, usize, W)]) -> Self {
+        let entries = edges
+            .iter()
+            .enumerate()
+            .map(|(id, (from, to, weight))| (*from, *to, weight.clone(), id))
+            .collect();
+        Self::build(nodes, edges.len(), entries)
+    }
+
+    pub fn undirected(nodes: usize, edges: &[(usize, usize, W)]) -> Self {
+        let mut entries = Vec::with_capacity(edges.len() * 2);
+        for (id, (a, b, weight)) in edges.iter().enumerate() {
+            entries.push((*a, *b, weight.clone(), id));
+            entries.push((*b, *a, weight.clone(), id));
+        }
+        Self::build(nodes, edges.len(), entries)
+    }
+
+    fn build(nodes: usize, edge_count: usize, entries: Vec<(usize, usize, W, usize)>) -> Self {
+        let mut start = vec![0; nodes + 1];
+        for &(from, to, _, _) in &entries {
+            assert!(from < nodes);
+            assert!(to < nodes);
+            start[from + 1] += 1;
+        }
+        for node in 0..nodes {
+            start[node + 1] += start[node];
+        }
+
+        let mut position = start.clone();
+        let mut places: Vec<usize> = entries
+            .iter()
+            .map(|&(from, _, _, _)| {
+                position[from] += 1;
+                position[from] - 1
+            })
+            .collect();
+        let mut list: Vec<_> = entries
+            .into_iter()
+            .map(|(_, to, weight, id)| CsrEdge { to, weight, id })
+            .collect();
+        for i in 0..list.len() {
+            while places[i] != i {
+                let place = places[i];
+                list.swap(i, place);
+                places.swap(i, place);
+            }
+        }
+
+        Csr {
+            nodes,
+            edge_count,
+            start,
+            list,
+        }
+    }
+
+    pub fn neighbors(&self, node: usize) -> &[CsrEdge<W>] {
+        &self.list[self.start[node]..self.start[node + 1]]
+    }
+
+    pub fn degree(&self, node: usize) -> usize {
+        self.start[node + 1] - self.start[node]
+    }
+
+    pub fn edges_by_id(&self) -> Vec<(usize, &CsrEdge<W>)> {
+        let mut edges: Vec<_> = (0..self.nodes)
+            .flat_map(|node| self.neighbors(node).iter().map(move |edge| (node, edge)))
+            .collect();
+        edges.sort_by_key(|&(_, edge)| edge.id);
+        edges
+    }
+}
+
+#[test]
+fn test_csr() {
+    use crate::graph::Graph;
+    use crate::maxflow::MaxFlow;
+    use crate::mincostflow::MinCostFlow;
+
+    let edges = [(0, 1, 4), (2, 0, 1), (0, 2, 7), (1, 3, 2), (2, 3, 5)];
+
+    let csr = Csr::directed(4, &edges);
+    assert_eq!(csr.edge_count, 5);
+    assert_eq!(csr.degree(0), 2);
+    assert_eq!(csr.degree(3), 0);
+    let targets: Vec<_> = csr
+        .neighbors(0)
+        .iter()
+        .map(|e| (e.to, e.weight, e.id))
+        .collect();
+    assert_eq!(targets, [(1, 4, 0), (2, 7, 2)]);
+    let order: Vec<_> = csr
+        .edges_by_id()
+        .iter()
+        .map(|&(from, e)| (from, e.to))
+        .collect();
+    assert_eq!(order, [(0, 1), (2, 0), (0, 2), (1, 3), (2, 3)]);
+
+    let mut expected = Graph::new(4);
+    for &(from, to, cost) in &edges {
+        expected.edge_directed_costed(from, to, cost);
+    }
+    let graph = Graph::from_csr(&csr);
+    assert_eq!(graph.edge_count, 5);
+    assert_eq!(graph.dijkstra(0), expected.dijkstra(0));
+
+    let mut network = MaxFlow::from_csr(&csr);
+    assert_eq!(network.max_flow(0, 3), 7);
+
+    let csr = Csr::undirected(4, &edges);
+    assert_eq!(csr.degree(0), 3);
+    assert_eq!(csr.degree(3), 2);
+    let ids: Vec<_> = csr.neighbors(2).iter().map(|e| e.id).collect();
+    assert_eq!(ids, [1, 2, 4]);
+
+    let graph = Graph::from_csr(&csr);
+    assert_eq!(graph.dijkstra(3), [Some(6), Some(2), Some(5), Some(0)]);
+    let mut network = MaxFlow::from_csr(&csr);
+    assert_eq!(network.max_flow(3, 0), 7);
+
+    let costed = [(0, 1, (2, 1)), (1, 2, (2, 1)), (0, 2, (3, 4))];
+    let csr = Csr::directed(3, &costed);
+    let mut network = MinCostFlow::from_csr(&csr);
+    assert_eq!(network.min_cost_max_flow(0, 2), (5, 16));
+}
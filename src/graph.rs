@@ -1,5 +1,8 @@
 #![allow(clippy::needless_range_loop)]
 
+use crate::csr::Csr;
+use crate::safe_index::SafeIndex;
+use crate::union_find::UnionFind;
 use cargo_snippet::snippet;
 
 #[snippet("graph")]
@@ -10,14 +13,15 @@ use std::collections::binary_heap::BinaryHeap;
 #[snippet("graph")]
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
 pub struct GraphEdge {
-    cost: isize,
-    node_to: usize,
+    pub cost: isize,
+    pub node_to: usize,
+    pub id: usize,
 }
 
 #[snippet("graph")]
 impl GraphEdge {
-    pub fn new(node_to: usize, cost: isize) -> Self {
-        Self { node_to, cost }
+    pub fn new(node_to: usize, cost: isize, id: usize) -> Self {
+        Self { node_to, cost, id }
     }
 }
 
@@ -25,6 +29,7 @@ impl GraphEdge {
 pub struct Graph {
     pub edges: Vec<Vec<GraphEdge>>,
     pub nodes: usize,
+    pub edge_count: usize,
 }
 
 #[snippet("graph")]
@@ -33,53 +38,57 @@ impl Graph {
         Self {
             edges: vec![vec![]; nodes],
             nodes,
+            edge_count: 0,
         }
     }
 
     pub fn edge_undirected(&mut self, node_a: usize, node_b: usize) {
-        self.edges[node_a].push(GraphEdge::new(node_b, 1));
-        self.edges[node_b].push(GraphEdge::new(node_a, 1));
+        self.edge_undirected_costed(node_a, node_b, 1);
     }
 
     pub fn edge_directed(&mut self, node_from: usize, node_to: usize) {
-        self.edges[node_from].push(GraphEdge::new(node_to, 1));
+        self.edge_directed_costed(node_from, node_to, 1);
     }
 
     pub fn edge_undirected_costed(&mut self, node_a: usize, node_b: usize, cost: isize) {
-        self.edges[node_a].push(GraphEdge::new(node_b, cost));
-        self.edges[node_b].push(GraphEdge::new(node_a, cost));
+        let id = self.edge_count;
+        self.edges[node_a].push(GraphEdge::new(node_b, cost, id));
+        self.edges[node_b].push(GraphEdge::new(node_a, cost, id));
+        self.edge_count += 1;
     }
 
     pub fn edge_directed_costed(&mut self, node_from: usize, node_to: usize, cost: isize) {
-        self.edges[node_from].push(GraphEdge::new(node_to, cost));
+        let id = self.edge_count;
+        self.edges[node_from].push(GraphEdge::new(node_to, cost, id));
+        self.edge_count += 1;
     }
 
     pub fn dijkstra(&self, node_from: usize) -> Vec<Option<isize>> {
         let mut costs = vec![None; self.nodes];
-        let mut heap: BinaryHeap<Reverse<GraphEdge>> = BinaryHeap::new();
-        heap.push(Reverse(GraphEdge::new(node_from, 0)));
-        while let Some(Reverse(GraphEdge { cost, node_to })) = heap.pop() {
+        let mut heap = BinaryHeap::new();
+        heap.push(Reverse((0, node_from)));
+        while let Some(Reverse((cost, node_to))) = heap.pop() {
             if cost >= costs[node_to].unwrap_or(std::isize::MAX) {
                 continue;
             }
             costs[node_to] = Some(cost);
 
             for edge in &self.edges[node_to] {
-                let next = GraphEdge::new(edge.node_to, cost + edge.cost);
-                heap.push(Reverse(next));
+                heap.push(Reverse((cost + edge.cost, edge.node_to)));
             }
         }
         costs
     }
 
-    pub fn warshall_floyd(&self) -> Vec<Vec<Option<isize>>> {
+    pub fn warshall_floyd(&self) -> Option<Vec<Vec<Option<isize>>>> {
         let mut costs = vec![vec![None; self.nodes]; self.nodes];
         for i in 0..self.nodes {
             costs[i][i] = Some(0);
         }
         for (node_from, edges) in self.edges.iter().enumerate() {
-            for GraphEdge { node_to, cost } in edges.iter() {
-                costs[node_from][*node_to] = Some(*cost);
+            for GraphEdge { node_to, cost, .. } in edges.iter() {
+                let current = costs[node_from][*node_to].unwrap_or(*cost);
+                costs[node_from][*node_to] = Some(current.min(*cost));
             }
         }
         for k in 0..self.nodes {
@@ -94,11 +103,845 @@ impl Graph {
                     };
                 }
             }
+            if (0..self.nodes).any(|i| costs[i][i] < Some(0)) {
+                return None;
+            }
+        }
+        Some(costs)
+    }
+}
+
+#[snippet(name = "graph_mst", include = "graph, union_find")]
+impl Graph {
+    fn undirected_edges(&self) -> Vec<(isize, usize, usize)> {
+        let mut edges = Vec::new();
+        for (node_from, adjacents) in self.edges.iter().enumerate() {
+            for &GraphEdge { node_to, cost, .. } in adjacents {
+                if node_from < node_to {
+                    edges.push((cost, node_from, node_to));
+                }
+            }
+        }
+        edges
+    }
+
+    pub fn kruskal(&self) -> (isize, Vec<(usize, usize, isize)>) {
+        let mut edges = self.undirected_edges();
+        edges.sort_unstable();
+
+        let mut uf = UnionFind::new(self.nodes);
+        let mut total = 0;
+        let mut chosen = Vec::new();
+        for (cost, node_a, node_b) in edges {
+            if !uf.same_root(node_a, node_b) {
+                uf.merge(node_a, node_b);
+                total += cost;
+                chosen.push((node_a, node_b, cost));
+            }
+        }
+        (total, chosen)
+    }
+
+    pub fn prim(&self) -> (isize, Vec<(usize, usize, isize)>) {
+        let mut visited = vec![false; self.nodes];
+        let mut total = 0;
+        let mut chosen = Vec::new();
+        let mut heap = BinaryHeap::new();
+
+        for root in 0..self.nodes {
+            if visited[root] {
+                continue;
+            }
+            visited[root] = true;
+            heap.extend(
+                self.edges[root]
+                    .iter()
+                    .map(|edge| Reverse((edge.cost, edge.node_to, root))),
+            );
+
+            while let Some(Reverse((cost, node_to, node_from))) = heap.pop() {
+                if visited[node_to] {
+                    continue;
+                }
+                visited[node_to] = true;
+                total += cost;
+                chosen.push((node_from, node_to, cost));
+
+                for edge in &self.edges[node_to] {
+                    if !visited[edge.node_to] {
+                        heap.push(Reverse((edge.cost, edge.node_to, node_to)));
+                    }
+                }
+            }
+        }
+        (total, chosen)
+    }
+
+    pub fn boruvka(&self) -> (isize, Vec<(usize, usize, isize)>) {
+        let edges = self.undirected_edges();
+        let mut uf = UnionFind::new(self.nodes);
+        let mut total = 0;
+        let mut chosen = Vec::new();
+
+        loop {
+            let mut cheapest: Vec<Option<usize>> = vec![None; self.nodes + 1];
+            for (i, &(cost, node_a, node_b)) in edges.iter().enumerate() {
+                let root_a = uf.root_of(node_a);
+                let root_b = uf.root_of(node_b);
+                if root_a == root_b {
+                    continue;
+                }
+                for &root in &[root_a, root_b] {
+                    cheapest[root] = match cheapest[root] {
+                        Some(j) if (edges[j].0, j) < (cost, i) => Some(j),
+                        _ => Some(i),
+                    };
+                }
+            }
+
+            let mut merged = false;
+            for i in cheapest.into_iter().flatten() {
+                let (cost, node_a, node_b) = edges[i];
+                if !uf.same_root(node_a, node_b) {
+                    uf.merge(node_a, node_b);
+                    total += cost;
+                    chosen.push((node_a, node_b, cost));
+                    merged = true;
+                }
+            }
+
+            if !merged {
+                break (total, chosen);
+            }
+        }
+    }
+}
+
+#[snippet(name = "graph_path", include = "graph")]
+pub struct ShortestPaths {
+    pub costs: Vec<Option<isize>>,
+    pub parents: Vec<Option<usize>>,
+}
+
+#[snippet("graph_path")]
+impl ShortestPaths {
+    pub fn path_to(&self, target: usize) -> Option<Vec<usize>> {
+        self.costs[target]?;
+
+        let mut path = vec![target];
+        let mut node = target;
+        while let Some(parent) = self.parents[node] {
+            path.push(parent);
+            node = parent;
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+#[snippet("graph_path")]
+impl Graph {
+    fn dijkstra_until(&self, starts: &[usize], target: Option<usize>) -> ShortestPaths {
+        let mut costs = vec![None; self.nodes];
+        let mut parents = vec![None; self.nodes];
+        let mut heap = BinaryHeap::new();
+        heap.extend(starts.iter().map(|&start| Reverse((0, start, None))));
+
+        while let Some(Reverse((cost, node, parent))) = heap.pop() {
+            if costs[node].is_some() {
+                continue;
+            }
+            costs[node] = Some(cost);
+            parents[node] = parent;
+
+            if target == Some(node) {
+                break;
+            }
+
+            for edge in &self.edges[node] {
+                if costs[edge.node_to].is_none() {
+                    heap.push(Reverse((cost + edge.cost, edge.node_to, Some(node))));
+                }
+            }
+        }
+
+        ShortestPaths { costs, parents }
+    }
+
+    pub fn dijkstra_with_parents(&self, starts: &[usize]) -> ShortestPaths {
+        self.dijkstra_until(starts, None)
+    }
+
+    pub fn dijkstra_to(&self, starts: &[usize], target: usize) -> Option<(isize, Vec<usize>)> {
+        let paths = self.dijkstra_until(starts, Some(target));
+        Some((paths.costs[target]?, paths.path_to(target)?))
+    }
+}
+
+#[snippet(name = "graph_negative", include = "graph")]
+impl Graph {
+    pub fn bellman_ford(&self, node_from: usize) -> Result<Vec<Option<isize>>, Vec<usize>> {
+        let mut costs = vec![None; self.nodes];
+        costs[node_from] = Some(0);
+
+        for _ in 1..self.nodes {
+            let mut updated = false;
+            for node in 0..self.nodes {
+                let cost = match costs[node] {
+                    Some(cost) => cost,
+                    None => continue,
+                };
+                for edge in &self.edges[node] {
+                    let next = Some(cost + edge.cost);
+                    if costs[edge.node_to].is_none() || next < costs[edge.node_to] {
+                        costs[edge.node_to] = next;
+                        updated = true;
+                    }
+                }
+            }
+            if !updated {
+                return Ok(costs);
+            }
+        }
+
+        let mut negative = vec![false; self.nodes];
+        for _ in 0..self.nodes {
+            for node in 0..self.nodes {
+                let cost = match costs[node] {
+                    Some(cost) => cost,
+                    None => continue,
+                };
+                for edge in &self.edges[node] {
+                    let next = Some(cost + edge.cost);
+                    if negative[node] || costs[edge.node_to].is_none() || next < costs[edge.node_to]
+                    {
+                        costs[edge.node_to] = next;
+                        negative[edge.node_to] = true;
+                    }
+                }
+            }
+        }
+
+        let negative: Vec<usize> = (0..self.nodes).filter(|&node| negative[node]).collect();
+        if negative.is_empty() {
+            Ok(costs)
+        } else {
+            Err(negative)
+        }
+    }
+
+    pub fn spfa(&self, node_from: usize) -> Result<Vec<Option<isize>>, Vec<usize>> {
+        let mut costs = vec![None; self.nodes];
+        let mut lengths = vec![0; self.nodes];
+        let mut queued = vec![false; self.nodes];
+        let mut queue = std::collections::VecDeque::new();
+        costs[node_from] = Some(0);
+        queue.push_back(node_from);
+        queued[node_from] = true;
+
+        while let Some(node) = queue.pop_front() {
+            queued[node] = false;
+            let cost = costs[node].unwrap();
+
+            for edge in &self.edges[node] {
+                let next = Some(cost + edge.cost);
+                if costs[edge.node_to].is_some() && costs[edge.node_to] <= next {
+                    continue;
+                }
+                costs[edge.node_to] = next;
+                lengths[edge.node_to] = lengths[node] + 1;
+
+                if lengths[edge.node_to] >= self.nodes {
+                    // A negative cycle exists; fall back to find every affected node.
+                    return self.bellman_ford(node_from);
+                }
+                if !queued[edge.node_to] {
+                    queue.push_back(edge.node_to);
+                    queued[edge.node_to] = true;
+                }
+            }
+        }
+
+        Ok(costs)
+    }
+}
+
+#[snippet(name = "graph_bfs", include = "graph")]
+impl Graph {
+    pub fn bfs(&self, node_from: usize) -> Vec<Option<usize>> {
+        let mut dists = vec![None; self.nodes];
+        let mut queue = std::collections::VecDeque::new();
+        dists[node_from] = Some(0);
+        queue.push_back(node_from);
+
+        while let Some(node) = queue.pop_front() {
+            let dist = dists[node].unwrap();
+            for edge in &self.edges[node] {
+                if dists[edge.node_to].is_none() {
+                    dists[edge.node_to] = Some(dist + 1);
+                    queue.push_back(edge.node_to);
+                }
+            }
+        }
+        dists
+    }
+
+    pub fn zero_one_bfs(&self, node_from: usize) -> Vec<Option<isize>> {
+        let mut costs = vec![None; self.nodes];
+        let mut done = vec![false; self.nodes];
+        let mut deque = std::collections::VecDeque::new();
+        costs[node_from] = Some(0);
+        deque.push_back(node_from);
+
+        while let Some(node) = deque.pop_front() {
+            if done[node] {
+                continue;
+            }
+            done[node] = true;
+            let cost = costs[node].unwrap();
+
+            for edge in &self.edges[node] {
+                assert!(edge.cost == 0 || edge.cost == 1);
+                let next = Some(cost + edge.cost);
+                if costs[edge.node_to].is_some() && costs[edge.node_to] <= next {
+                    continue;
+                }
+                costs[edge.node_to] = next;
+                if edge.cost == 0 {
+                    deque.push_front(edge.node_to);
+                } else {
+                    deque.push_back(edge.node_to);
+                }
+            }
         }
         costs
     }
 }
 
+#[snippet(name = "grid", include = "index")]
+const GRID_DIRECTIONS: [(isize, isize); 8] = [
+    (-1, 0),
+    (0, -1),
+    (0, 1),
+    (1, 0),
+    (-1, -1),
+    (-1, 1),
+    (1, -1),
+    (1, 1),
+];
+
+#[snippet("grid")]
+pub struct Grid {
+    pub cells: Vec<Vec<u8>>,
+    walls: Vec<u8>,
+    directions: usize,
+}
+
+#[snippet("grid")]
+impl Grid {
+    pub fn new(cells: Vec<Vec<u8>>, walls: &[u8], diagonal: bool) -> Self {
+        Self {
+            cells,
+            walls: walls.to_vec(),
+            directions: if diagonal { 8 } else { 4 },
+        }
+    }
+
+    pub fn cell(&self, row: isize, col: isize) -> Option<u8> {
+        self.cells.at(row)?.at(col).copied()
+    }
+
+    pub fn is_open(&self, row: isize, col: isize) -> bool {
+        matches!(self.cell(row, col), Some(cell) if !self.walls.contains(&cell))
+    }
+
+    pub fn find(&self, target: u8) -> Option<(usize, usize)> {
+        self.cells.iter().enumerate().find_map(|(row, cells)| {
+            cells
+                .iter()
+                .position(|&cell| cell == target)
+                .map(|col| (row, col))
+        })
+    }
+
+    pub fn neighbors(&self, row: usize, col: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        GRID_DIRECTIONS[..self.directions]
+            .iter()
+            .map(move |&(dr, dc)| (row as isize + dr, col as isize + dc))
+            .filter(move |&(row, col)| self.is_open(row, col))
+            .map(|(row, col)| (row as usize, col as usize))
+    }
+
+    pub fn bfs(&self, starts: &[(usize, usize)]) -> Vec<Vec<Option<usize>>> {
+        let mut dists: Vec<Vec<Option<usize>>> = self
+            .cells
+            .iter()
+            .map(|cells| vec![None; cells.len()])
+            .collect();
+        let mut queue = std::collections::VecDeque::new();
+        for &(row, col) in starts {
+            if dists[row][col].is_none() {
+                dists[row][col] = Some(0);
+                queue.push_back((row, col));
+            }
+        }
+
+        while let Some((row, col)) = queue.pop_front() {
+            let dist = dists[row][col].unwrap();
+            for (next_row, next_col) in self.neighbors(row, col) {
+                if dists[next_row][next_col].is_none() {
+                    dists[next_row][next_col] = Some(dist + 1);
+                    queue.push_back((next_row, next_col));
+                }
+            }
+        }
+        dists
+    }
+}
+
+#[snippet(name = "graph_topological", include = "graph")]
+impl Graph {
+    fn in_degrees(&self) -> Vec<usize> {
+        let mut in_degrees = vec![0; self.nodes];
+        for edge in self.edges.iter().flatten() {
+            in_degrees[edge.node_to] += 1;
+        }
+        in_degrees
+    }
+
+    pub fn topological_sort(&self) -> Option<Vec<usize>> {
+        let mut in_degrees = self.in_degrees();
+        let mut queue: std::collections::VecDeque<usize> = (0..self.nodes)
+            .filter(|&node| in_degrees[node] == 0)
+            .collect();
+        let mut order = Vec::with_capacity(self.nodes);
+
+        while let Some(node) = queue.pop_front() {
+            order.push(node);
+            for edge in &self.edges[node] {
+                in_degrees[edge.node_to] -= 1;
+                if in_degrees[edge.node_to] == 0 {
+                    queue.push_back(edge.node_to);
+                }
+            }
+        }
+
+        if order.len() == self.nodes {
+            Some(order)
+        } else {
+            None
+        }
+    }
+
+    pub fn topological_sort_min(&self) -> Option<Vec<usize>> {
+        let mut in_degrees = self.in_degrees();
+        let mut heap: BinaryHeap<Reverse<usize>> = (0..self.nodes)
+            .filter(|&node| in_degrees[node] == 0)
+            .map(Reverse)
+            .collect();
+        let mut order = Vec::with_capacity(self.nodes);
+
+        while let Some(Reverse(node)) = heap.pop() {
+            order.push(node);
+            for edge in &self.edges[node] {
+                in_degrees[edge.node_to] -= 1;
+                if in_degrees[edge.node_to] == 0 {
+                    heap.push(Reverse(edge.node_to));
+                }
+            }
+        }
+
+        if order.len() == self.nodes {
+            Some(order)
+        } else {
+            None
+        }
+    }
+
+    pub fn find_cycle(&self) -> Option<Vec<usize>> {
+        let mut visited = vec![false; self.nodes];
+        let mut position: Vec<Option<usize>> = vec![None; self.nodes];
+
+        for root in 0..self.nodes {
+            if visited[root] {
+                continue;
+            }
+            visited[root] = true;
+            position[root] = Some(0);
+            let mut stack = vec![(root, 0)];
+
+            while let Some((node, i)) = stack.last_mut() {
+                let node = *node;
+                if let Some(edge) = self.edges[node].get(*i) {
+                    *i += 1;
+                    if let Some(begin) = position[edge.node_to] {
+                        return Some(stack[begin..].iter().map(|&(node, _)| node).collect());
+                    }
+                    if !visited[edge.node_to] {
+                        visited[edge.node_to] = true;
+                        position[edge.node_to] = Some(stack.len());
+                        stack.push((edge.node_to, 0));
+                    }
+                } else {
+                    position[node] = None;
+                    stack.pop();
+                }
+            }
+        }
+
+        None
+    }
+
+    pub fn dag_longest_path(&self) -> Option<Vec<isize>> {
+        let mut lengths = vec![0; self.nodes];
+        for node in self.topological_sort()? {
+            for edge in &self.edges[node] {
+                lengths[edge.node_to] = lengths[edge.node_to].max(lengths[node] + edge.cost);
+            }
+        }
+        Some(lengths)
+    }
+}
+
+#[snippet(name = "graph_scc", include = "graph")]
+impl Graph {
+    pub fn scc_ids(&self) -> (usize, Vec<usize>) {
+        let mut order = vec![None; self.nodes];
+        let mut low = vec![0; self.nodes];
+        let mut on_stack = vec![false; self.nodes];
+        let mut stack = Vec::new();
+        let mut ids = vec![0; self.nodes];
+        let mut count = 0;
+        let mut time = 0;
+
+        for root in 0..self.nodes {
+            if order[root].is_some() {
+                continue;
+            }
+            order[root] = Some(time);
+            low[root] = time;
+            time += 1;
+            stack.push(root);
+            on_stack[root] = true;
+            let mut calls = vec![(root, 0)];
+
+            while let Some(&(node, i)) = calls.last() {
+                if let Some(edge) = self.edges[node].get(i) {
+                    calls.last_mut().unwrap().1 += 1;
+                    match order[edge.node_to] {
+                        None => {
+                            order[edge.node_to] = Some(time);
+                            low[edge.node_to] = time;
+                            time += 1;
+                            stack.push(edge.node_to);
+                            on_stack[edge.node_to] = true;
+                            calls.push((edge.node_to, 0));
+                        }
+                        Some(order_to) if on_stack[edge.node_to] => {
+                            low[node] = low[node].min(order_to);
+                        }
+                        _ => {}
+                    }
+                    continue;
+                }
+
+                calls.pop();
+                if let Some(&(parent, _)) = calls.last() {
+                    low[parent] = low[parent].min(low[node]);
+                }
+                if Some(low[node]) == order[node] {
+                    while let Some(member) = stack.pop() {
+                        on_stack[member] = false;
+                        ids[member] = count;
+                        if member == node {
+                            break;
+                        }
+                    }
+                    count += 1;
+                }
+            }
+        }
+
+        for id in ids.iter_mut() {
+            *id = count - 1 - *id;
+        }
+        (count, ids)
+    }
+
+    pub fn scc(&self) -> Vec<Vec<usize>> {
+        let (count, ids) = self.scc_ids();
+        let mut components = vec![Vec::new(); count];
+        for (node, &id) in ids.iter().enumerate() {
+            components[id].push(node);
+        }
+        components
+    }
+
+    pub fn condensation(&self) -> (Vec<usize>, Graph) {
+        let (count, ids) = self.scc_ids();
+        let mut dag = Graph::new(count);
+        for (node_from, edges) in self.edges.iter().enumerate() {
+            for edge in edges {
+                if ids[node_from] != ids[edge.node_to] {
+                    dag.edge_directed_costed(ids[node_from], ids[edge.node_to], edge.cost);
+                }
+            }
+        }
+        (ids, dag)
+    }
+}
+
+#[snippet(name = "graph_lowlink", include = "graph")]
+pub struct LowLink {
+    pub order: Vec<usize>,
+    pub low: Vec<usize>,
+    pub bridges: Vec<usize>,
+    pub articulation_points: Vec<usize>,
+    pub blocks: Vec<Vec<usize>>,
+}
+
+#[snippet("graph_lowlink")]
+impl Graph {
+    pub fn lowlink(&self) -> LowLink {
+        let mut order = vec![usize::MAX; self.nodes];
+        let mut low = vec![usize::MAX; self.nodes];
+        let mut is_articulation = vec![false; self.nodes];
+        let mut bridges = Vec::new();
+        let mut blocks = Vec::new();
+        let mut visiting = Vec::new();
+        let mut time = 0;
+
+        for root in 0..self.nodes {
+            if order[root] != usize::MAX {
+                continue;
+            }
+            order[root] = time;
+            low[root] = time;
+            time += 1;
+            visiting.push(root);
+            let mut root_children = 0;
+            let mut calls = vec![(root, None, 0)];
+
+            while let Some(&(node, parent_edge, i)) = calls.last() {
+                if let Some(edge) = self.edges[node].get(i) {
+                    calls.last_mut().unwrap().2 += 1;
+                    if parent_edge == Some(edge.id) {
+                        continue;
+                    }
+                    if order[edge.node_to] == usize::MAX {
+                        order[edge.node_to] = time;
+                        low[edge.node_to] = time;
+                        time += 1;
+                        visiting.push(edge.node_to);
+                        calls.push((edge.node_to, Some(edge.id), 0));
+                    } else {
+                        low[node] = low[node].min(order[edge.node_to]);
+                    }
+                    continue;
+                }
+
+                calls.pop();
+                let parent = match calls.last() {
+                    Some(&(parent, _, _)) => parent,
+                    None => {
+                        visiting.pop();
+                        if root_children == 0 {
+                            blocks.push(vec![root]);
+                        }
+                        break;
+                    }
+                };
+
+                low[parent] = low[parent].min(low[node]);
+                if low[node] > order[parent] {
+                    bridges.push(parent_edge.unwrap());
+                }
+                if parent == root {
+                    root_children += 1;
+                    is_articulation[root] = root_children >= 2;
+                }
+                if low[node] >= order[parent] {
+                    if parent != root {
+                        is_articulation[parent] = true;
+                    }
+                    let mut block = vec![parent];
+                    while let Some(member) = visiting.pop() {
+                        block.push(member);
+                        if member == node {
+                            break;
+                        }
+                    }
+                    blocks.push(block);
+                }
+            }
+        }
+
+        bridges.sort_unstable();
+        let articulation_points = (0..self.nodes)
+            .filter(|&node| is_articulation[node])
+            .collect();
+        LowLink {
+            order,
+            low,
+            bridges,
+            articulation_points,
+            blocks,
+        }
+    }
+
+    pub fn bridges(&self) -> Vec<usize> {
+        self.lowlink().bridges
+    }
+
+    pub fn articulation_points(&self) -> Vec<usize> {
+        self.lowlink().articulation_points
+    }
+
+    pub fn biconnected_components(&self) -> Vec<Vec<usize>> {
+        self.lowlink().blocks
+    }
+
+    pub fn two_edge_connected_components(&self) -> Vec<Vec<usize>> {
+        let mut is_bridge = vec![false; self.edge_count];
+        for id in self.bridges() {
+            is_bridge[id] = true;
+        }
+
+        let mut visited = vec![false; self.nodes];
+        let mut components = Vec::new();
+        for root in 0..self.nodes {
+            if visited[root] {
+                continue;
+            }
+            visited[root] = true;
+            let mut component = vec![root];
+            let mut stack = vec![root];
+            while let Some(node) = stack.pop() {
+                for edge in &self.edges[node] {
+                    if !is_bridge[edge.id] && !visited[edge.node_to] {
+                        visited[edge.node_to] = true;
+                        component.push(edge.node_to);
+                        stack.push(edge.node_to);
+                    }
+                }
+            }
+            component.sort_unstable();
+            components.push(component);
+        }
+        components
+    }
+
+    pub fn block_cut_tree(&self) -> Graph {
+        let blocks = self.biconnected_components();
+        let mut tree = Graph::new(self.nodes + blocks.len());
+        for (i, block) in blocks.iter().enumerate() {
+            for &node in block {
+                tree.edge_undirected(self.nodes + i, node);
+            }
+        }
+        tree
+    }
+}
+
+#[snippet(name = "graph_bipartite", include = "graph")]
+impl Graph {
+    pub fn bipartite_coloring(&self) -> Result<Vec<bool>, Vec<usize>> {
+        let mut colors = vec![None; self.nodes];
+        let mut parents = vec![None; self.nodes];
+        let mut queue = std::collections::VecDeque::new();
+
+        for root in 0..self.nodes {
+            if colors[root].is_some() {
+                continue;
+            }
+            colors[root] = Some(false);
+            queue.push_back(root);
+
+            while let Some(node) = queue.pop_front() {
+                let color = colors[node].unwrap();
+                for edge in &self.edges[node] {
+                    match colors[edge.node_to] {
+                        None => {
+                            colors[edge.node_to] = Some(!color);
+                            parents[edge.node_to] = Some(node);
+                            queue.push_back(edge.node_to);
+                        }
+                        Some(c) if c == color => {
+                            let mut a = vec![node];
+                            let mut b = vec![edge.node_to];
+                            while a.last() != b.last() {
+                                let (x, y) = (*a.last().unwrap(), *b.last().unwrap());
+                                a.push(parents[x].unwrap());
+                                b.push(parents[y].unwrap());
+                            }
+                            b.pop();
+                            a.extend(b.into_iter().rev());
+                            return Err(a);
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        Ok(colors.into_iter().map(Option::unwrap).collect())
+    }
+}
+
+#[snippet(name = "graph_dot", include = "graph")]
+impl Graph {
+    pub fn to_dot(&self) -> String {
+        let mut directions = vec![0; self.edge_count];
+        for edges in &self.edges {
+            for edge in edges {
+                directions[edge.id] += 1;
+            }
+        }
+
+        let mut dot = String::from("digraph {\n");
+        for node in 0..self.nodes {
+            dot.push_str(&format!("    {};\n", node));
+        }
+        let mut printed = vec![false; self.edge_count];
+        for (node, edges) in self.edges.iter().enumerate() {
+            for edge in edges {
+                if printed[edge.id] {
+                    continue;
+                }
+                printed[edge.id] = true;
+
+                let dir = if directions[edge.id] == 2 {
+                    ", dir=none"
+                } else {
+                    ""
+                };
+                dot.push_str(&format!(
+                    "    {} -> {} [label=\"{}\"{}];\n",
+                    node, edge.node_to, edge.cost, dir
+                ));
+            }
+        }
+        dot.push_str("}\n");
+
+        dot
+    }
+}
+
+#[snippet(name = "graph_csr", include = "graph, csr")]
+impl Graph {
+    pub fn from_csr(csr: &Csr<isize>) -> Self {
+        let mut graph = Graph::new(csr.nodes);
+        for node in 0..csr.nodes {
+            for edge in csr.neighbors(node) {
+                graph.edges[node].push(GraphEdge::new(edge.to, edge.weight, edge.id));
+            }
+        }
+        graph.edge_count = csr.edge_count;
+
+        graph
+    }
+}
+
 #[test]
 fn test_graph() {
     let mut graph = Graph::new(8);
@@ -143,5 +986,317 @@ fn test_graph() {
         vec![Some(14), Some(9), Some(8), Some(6), Some(0), None],
         vec![None, None, None, None, None, Some(0)],
     ];
-    assert_eq!(expected, graph.warshall_floyd());
+    assert_eq!(Some(expected), graph.warshall_floyd());
+}
+
+#[test]
+fn test_mst() {
+    let mut graph = Graph::new(12);
+    graph.edge_undirected_costed(0, 7, 2);
+    graph.edge_undirected_costed(1, 7, 4);
+    graph.edge_undirected_costed(0, 3, 9);
+    graph.edge_undirected_costed(1, 3, 3);
+    graph.edge_undirected_costed(0, 4, 13);
+    graph.edge_undirected_costed(3, 4, 3);
+    graph.edge_undirected_costed(3, 5, 5);
+    graph.edge_undirected_costed(1, 5, 6);
+    graph.edge_undirected_costed(2, 4, 8);
+    graph.edge_undirected_costed(2, 3, 11);
+    graph.edge_undirected_costed(2, 6, 4);
+    graph.edge_undirected_costed(3, 6, 6);
+    graph.edge_undirected_costed(5, 6, 5);
+    graph.edge_undirected_costed(8, 9, 7);
+    graph.edge_undirected_costed(9, 10, -2);
+    graph.edge_undirected_costed(8, 10, 1);
+    graph.edge_undirected_costed(8, 8, -5);
+
+    for (total, chosen) in [graph.kruskal(), graph.prim(), graph.boruvka()] {
+        assert_eq!(total, 25);
+        assert_eq!(chosen.len(), 9);
+        assert_eq!(chosen.iter().map(|&(_, _, cost)| cost).sum::<isize>(), 25);
+
+        let mut uf = UnionFind::new(12);
+        for &(node_a, node_b, _) in &chosen {
+            assert!(!uf.same_root(node_a, node_b));
+            uf.merge(node_a, node_b);
+        }
+        assert!(uf.same_root(0, 6));
+        assert!(uf.same_root(8, 10));
+        assert!(!uf.same_root(0, 8));
+    }
+}
+
+#[test]
+fn test_dijkstra_with_parents() {
+    let mut graph = Graph::new(7);
+    graph.edge_directed_costed(0, 1, 4);
+    graph.edge_directed_costed(0, 2, 1);
+    graph.edge_directed_costed(2, 1, 2);
+    graph.edge_directed_costed(1, 3, 1);
+    graph.edge_directed_costed(2, 3, 5);
+    graph.edge_directed_costed(3, 4, 3);
+    graph.edge_directed_costed(5, 4, 1);
+
+    let paths = graph.dijkstra_with_parents(&[0]);
+    assert_eq!(paths.costs, graph.dijkstra(0));
+    assert_eq!(paths.path_to(0), Some(vec![0]));
+    assert_eq!(paths.path_to(3), Some(vec![0, 2, 1, 3]));
+    assert_eq!(paths.path_to(4), Some(vec![0, 2, 1, 3, 4]));
+    assert_eq!(paths.path_to(5), None);
+    assert_eq!(paths.path_to(6), None);
+
+    let paths = graph.dijkstra_with_parents(&[0, 5]);
+    assert_eq!(paths.costs[4], Some(1));
+    assert_eq!(paths.path_to(4), Some(vec![5, 4]));
+    assert_eq!(paths.path_to(3), Some(vec![0, 2, 1, 3]));
+
+    assert_eq!(graph.dijkstra_to(&[0], 3), Some((4, vec![0, 2, 1, 3])));
+    assert_eq!(graph.dijkstra_to(&[0, 5], 4), Some((1, vec![5, 4])));
+    assert_eq!(graph.dijkstra_to(&[1], 2), None);
+}
+
+#[test]
+fn test_negative_costs() {
+    let mut graph = Graph::new(7);
+    graph.edge_directed_costed(0, 1, 4);
+    graph.edge_directed_costed(0, 2, 2);
+    graph.edge_directed_costed(2, 1, -3);
+    graph.edge_directed_costed(1, 3, 2);
+    graph.edge_directed_costed(3, 4, -1);
+    graph.edge_directed_costed(5, 6, -4);
+    let expected = vec![Some(0), Some(-1), Some(2), Some(1), Some(0), None, None];
+    assert_eq!(graph.bellman_ford(0), Ok(expected.clone()));
+    assert_eq!(graph.spfa(0), Ok(expected));
+    assert!(graph.warshall_floyd().is_some());
+
+    graph.edge_directed_costed(4, 1, -2);
+    graph.edge_directed_costed(4, 5, 1);
+    assert_eq!(graph.bellman_ford(0), Err(vec![1, 3, 4, 5, 6]));
+    assert_eq!(graph.spfa(0), Err(vec![1, 3, 4, 5, 6]));
+    assert_eq!(
+        graph.bellman_ford(6),
+        Ok(vec![None, None, None, None, None, None, Some(0)])
+    );
+    assert_eq!(graph.warshall_floyd(), None);
+
+    let mut graph = Graph::new(2);
+    graph.edge_directed_costed(0, 1, 1);
+    graph.edge_directed_costed(1, 1, -1);
+    assert_eq!(graph.bellman_ford(0), Err(vec![1]));
+    assert_eq!(graph.spfa(0), Err(vec![1]));
+    assert_eq!(graph.warshall_floyd(), None);
+}
+
+#[test]
+fn test_bfs() {
+    let mut graph = Graph::new(6);
+    graph.edge_directed_costed(0, 1, 1);
+    graph.edge_directed_costed(0, 2, 0);
+    graph.edge_directed_costed(2, 3, 1);
+    graph.edge_directed_costed(1, 3, 0);
+    graph.edge_directed_costed(3, 4, 0);
+    graph.edge_directed_costed(2, 1, 0);
+    assert_eq!(
+        graph.bfs(0),
+        vec![Some(0), Some(1), Some(1), Some(2), Some(3), None]
+    );
+    assert_eq!(graph.zero_one_bfs(0), graph.dijkstra(0));
+    assert_eq!(
+        graph.zero_one_bfs(0),
+        vec![Some(0), Some(0), Some(0), Some(0), Some(0), None]
+    );
+
+    let maze: Vec<Vec<u8>> = ["S.#..", ".##.#", "...#G", "#.#.."]
+        .iter()
+        .map(|row| row.bytes().collect())
+        .collect();
+    let grid = Grid::new(maze.clone(), b"#", false);
+    let start = grid.find(b'S').unwrap();
+    let goal = grid.find(b'G').unwrap();
+    assert_eq!((start, goal), ((0, 0), (2, 4)));
+    assert!(!grid.is_open(-1, 0));
+    assert!(!grid.is_open(0, 5));
+    assert!(!grid.is_open(0, 2));
+    assert_eq!(
+        grid.neighbors(0, 0).collect::<Vec<_>>(),
+        vec![(0, 1), (1, 0)]
+    );
+    let dists = grid.bfs(&[start]);
+    assert_eq!(dists[goal.0][goal.1], None);
+    assert_eq!(dists[3][1], Some(4));
+    assert_eq!(dists[0][3], None);
+
+    let grid = Grid::new(maze, b"#", true);
+    let dists = grid.bfs(&[start]);
+    assert_eq!(dists[goal.0][goal.1], Some(5));
+    assert_eq!(dists[0][3], Some(5));
+}
+
+#[test]
+fn test_topological_sort() {
+    let mut graph = Graph::new(6);
+    graph.edge_directed_costed(5, 2, 3);
+    graph.edge_directed_costed(5, 0, 1);
+    graph.edge_directed_costed(4, 0, 4);
+    graph.edge_directed_costed(4, 1, 2);
+    graph.edge_directed_costed(2, 3, 5);
+    graph.edge_directed_costed(3, 1, 1);
+
+    let order = graph.topological_sort().unwrap();
+    let mut position = [0; 6];
+    for (i, &node) in order.iter().enumerate() {
+        position[node] = i;
+    }
+    for (node_from, edges) in graph.edges.iter().enumerate() {
+        for edge in edges {
+            assert!(position[node_from] < position[edge.node_to]);
+        }
+    }
+    assert_eq!(graph.topological_sort_min(), Some(vec![4, 5, 0, 2, 3, 1]));
+    assert_eq!(graph.find_cycle(), None);
+    assert_eq!(graph.dag_longest_path(), Some(vec![4, 9, 3, 8, 0, 0]));
+
+    graph.edge_directed(1, 5);
+    assert_eq!(graph.topological_sort(), None);
+    assert_eq!(graph.topological_sort_min(), None);
+    assert_eq!(graph.dag_longest_path(), None);
+    assert_eq!(graph.find_cycle(), Some(vec![1, 5, 2, 3]));
+
+    let mut graph = Graph::new(3);
+    graph.edge_directed(0, 1);
+    graph.edge_directed(2, 2);
+    assert_eq!(graph.find_cycle(), Some(vec![2]));
+}
+
+#[test]
+fn test_scc() {
+    let mut graph = Graph::new(8);
+    graph.edge_directed(0, 1);
+    graph.edge_directed(1, 2);
+    graph.edge_directed(2, 0);
+    graph.edge_directed(2, 3);
+    graph.edge_directed(3, 4);
+    graph.edge_directed(4, 5);
+    graph.edge_directed(5, 3);
+    graph.edge_directed(6, 5);
+    graph.edge_directed(6, 7);
+    graph.edge_directed(7, 6);
+
+    let components = graph.scc();
+    assert_eq!(components.len(), 3);
+    let (ids, dag) = graph.condensation();
+    assert_eq!(components[ids[0]], vec![0, 1, 2]);
+    assert_eq!(components[ids[3]], vec![3, 4, 5]);
+    assert_eq!(components[ids[6]], vec![6, 7]);
+    assert!(ids[0] < ids[3]);
+    assert!(ids[6] < ids[3]);
+    assert_eq!(dag.nodes, 3);
+    assert!(dag.topological_sort().is_some());
+    for (node_from, edges) in dag.edges.iter().enumerate() {
+        for edge in edges {
+            assert!(node_from < edge.node_to);
+        }
+    }
+}
+
+#[test]
+fn test_lowlink() {
+    let mut graph = Graph::new(9);
+    graph.edge_undirected(0, 1);
+    graph.edge_undirected(1, 2);
+    graph.edge_undirected(2, 0);
+    graph.edge_undirected(2, 3);
+    graph.edge_undirected(3, 4);
+    graph.edge_undirected(3, 4);
+    graph.edge_undirected(4, 5);
+    graph.edge_undirected(5, 6);
+    graph.edge_undirected(6, 4);
+    graph.edge_undirected(6, 7);
+    graph.edge_undirected(7, 7);
+
+    assert_eq!(graph.bridges(), vec![3, 9]);
+    assert_eq!(graph.articulation_points(), vec![2, 3, 4, 6]);
+    assert_eq!(
+        graph.two_edge_connected_components(),
+        vec![vec![0, 1, 2], vec![3, 4, 5, 6], vec![7], vec![8]]
+    );
+
+    let mut blocks = graph.biconnected_components();
+    for block in blocks.iter_mut() {
+        block.sort_unstable();
+    }
+    blocks.sort();
+    assert_eq!(
+        blocks,
+        vec![
+            vec![0, 1, 2],
+            vec![2, 3],
+            vec![3, 4],
+            vec![4, 5, 6],
+            vec![6, 7],
+            vec![8]
+        ]
+    );
+
+    let tree = graph.block_cut_tree();
+    assert_eq!(tree.nodes, 15);
+    assert_eq!(tree.edge_count, 13);
+    assert_eq!(tree.bridges().len(), 13);
+    assert_eq!(tree.edges[3].len(), 2);
+    assert_eq!(tree.edges[4].len(), 2);
+    assert_eq!(tree.edges[0].len(), 1);
+}
+
+#[test]
+fn test_bipartite_coloring() {
+    let mut graph = Graph::new(7);
+    graph.edge_undirected(0, 1);
+    graph.edge_undirected(1, 2);
+    graph.edge_undirected(2, 3);
+    graph.edge_undirected(3, 0);
+    graph.edge_undirected(4, 5);
+    let colors = graph.bipartite_coloring().unwrap();
+    for (node_from, edges) in graph.edges.iter().enumerate() {
+        for edge in edges {
+            assert_ne!(colors[node_from], colors[edge.node_to]);
+        }
+    }
+
+    graph.edge_undirected(5, 6);
+    graph.edge_undirected(6, 4);
+    let cycle = graph.bipartite_coloring().unwrap_err();
+    assert_eq!(cycle.len() % 2, 1);
+    let mut sorted = cycle.clone();
+    sorted.sort_unstable();
+    assert_eq!(sorted, vec![4, 5, 6]);
+
+    let mut graph = Graph::new(6);
+    for node in 0..5 {
+        graph.edge_undirected(node, node + 1);
+    }
+    graph.edge_undirected(5, 1);
+    let cycle = graph.bipartite_coloring().unwrap_err();
+    assert_eq!(cycle.len(), 5);
+    for i in 0..cycle.len() {
+        let (a, b) = (cycle[i], cycle[(i + 1) % cycle.len()]);
+        assert!(graph.edges[a].iter().any(|edge| edge.node_to == b));
+    }
+}
+
+#[test]
+fn test_graph_to_dot() {
+    let mut graph = Graph::new(3);
+    graph.edge_undirected_costed(0, 1, 5);
+    graph.edge_directed_costed(1, 2, 3);
+    graph.edge_directed(2, 1);
+    graph.edge_undirected(2, 2);
+
+    assert_eq!(
+        graph.to_dot(),
+        "digraph {\n    0;\n    1;\n    2;\n    \
+         0 -> 1 [label=\"5\", dir=none];\n    \
+         1 -> 2 [label=\"3\"];\n    \
+         2 -> 1 [label=\"1\"];\n    \
+         2 -> 2 [label=\"1\", dir=none];\n}\n"
+    );
 }
@@ -0,0 +1,322 @@
+use crate::graph::Graph;
+use crate::segtree::{LazySegTree, Monoid, Morphism, SegTree};
+use cargo_snippet::snippet;
+
+#[snippet(name = "hld", include = "graph")]
+pub struct HeavyLightDecomposition {
+    pub parent: Vec<Option<usize>>,
+    pub depth: Vec<usize>,
+    pub head: Vec<usize>,
+    pub index: Vec<usize>,
+    pub size: Vec<usize>,
+}
+
+#[snippet("hld")]
+impl HeavyLightDecomposition {
+    pub fn new(graph: &Graph, root: usize) -> Self {
+        let nodes = graph.nodes;
+        let mut parent = vec![None; nodes];
+        let mut depth = vec![0; nodes];
+        let mut order = Vec::with_capacity(nodes);
+        let mut stack = vec![root];
+
+        while let Some(node) = stack.pop() {
+            order.push(node);
+            for edge in &graph.edges[node] {
+                if Some(edge.node_to) != parent[node] {
+                    parent[edge.node_to] = Some(node);
+                    depth[edge.node_to] = depth[node] + 1;
+                    stack.push(edge.node_to);
+                }
+            }
+        }
+
+        let mut size = vec![1; nodes];
+        let mut heavy = vec![None; nodes];
+        for &node in order.iter().rev() {
+            if let Some(p) = parent[node] {
+                size[p] += size[node];
+                heavy[p] = match heavy[p] {
+                    Some(h) if size[node] <= size[h] => Some(h),
+                    _ => Some(node),
+                };
+            }
+        }
+
+        let mut head = vec![root; nodes];
+        let mut index = vec![0; nodes];
+        let mut stack = vec![root];
+        let mut next_index = 0;
+
+        while let Some(node) = stack.pop() {
+            index[node] = next_index;
+            next_index += 1;
+            for edge in &graph.edges[node] {
+                if Some(edge.node_to) != parent[node] && Some(edge.node_to) != heavy[node] {
+                    head[edge.node_to] = edge.node_to;
+                    stack.push(edge.node_to);
+                }
+            }
+            if let Some(h) = heavy[node] {
+                head[h] = head[node];
+                stack.push(h);
+            }
+        }
+
+        Self {
+            parent,
+            depth,
+            head,
+            index,
+            size,
+        }
+    }
+
+    pub fn lca(&self, mut a: usize, mut b: usize) -> usize {
+        while self.head[a] != self.head[b] {
+            if self.index[self.head[a]] > self.index[self.head[b]] {
+                a = self.parent[self.head[a]].unwrap();
+            } else {
+                b = self.parent[self.head[b]].unwrap();
+            }
+        }
+        if self.depth[a] < self.depth[b] {
+            a
+        } else {
+            b
+        }
+    }
+
+    pub fn subtree(&self, node: usize, edge: bool) -> (usize, usize) {
+        (
+            self.index[node] + edge as usize,
+            self.index[node] + self.size[node],
+        )
+    }
+
+    pub fn path(&self, from: usize, to: usize, edge: bool) -> Vec<(usize, usize, bool)> {
+        let mut up = Vec::new();
+        let mut down = Vec::new();
+        let (mut a, mut b) = (from, to);
+
+        while self.head[a] != self.head[b] {
+            if self.index[self.head[a]] > self.index[self.head[b]] {
+                up.push((self.index[self.head[a]], self.index[a] + 1, true));
+                a = self.parent[self.head[a]].unwrap();
+            } else {
+                down.push((self.index[self.head[b]], self.index[b] + 1, false));
+                b = self.parent[self.head[b]].unwrap();
+            }
+        }
+
+        if self.index[a] > self.index[b] {
+            up.push((self.index[b] + edge as usize, self.index[a] + 1, true));
+        } else {
+            down.push((self.index[a] + edge as usize, self.index[b] + 1, false));
+        }
+
+        up.into_iter()
+            .chain(down.into_iter().rev())
+            .filter(|&(l, r, _)| l < r)
+            .collect()
+    }
+}
+
+#[snippet(name = "hld_segtree", include = "hld, segtree")]
+impl HeavyLightDecomposition {
+    pub fn path_product<M: Monoid, R: Fn(M::S) -> M::S>(
+        &self,
+        segtree: &SegTree<M>,
+        from: usize,
+        to: usize,
+        edge: bool,
+        reverse: R,
+    ) -> M::S {
+        self.path(from, to, edge)
+            .into_iter()
+            .fold(M::ID, |acc, (l, r, reversed)| {
+                let product = segtree.product(l, r);
+                let product = if reversed { reverse(product) } else { product };
+                M::product(acc, product)
+            })
+    }
+
+    pub fn subtree_product<M: Monoid>(
+        &self,
+        segtree: &SegTree<M>,
+        node: usize,
+        edge: bool,
+    ) -> M::S {
+        let (l, r) = self.subtree(node, edge);
+        segtree.product(l, r)
+    }
+}
+
+#[snippet(name = "hld_lazy_segtree", include = "hld, lazy_segtree")]
+impl HeavyLightDecomposition {
+    pub fn path_product_lazy<M: Monoid, F: Morphism<M::S>, R: Fn(M::S) -> M::S>(
+        &self,
+        segtree: &mut LazySegTree<M, F>,
+        from: usize,
+        to: usize,
+        edge: bool,
+        reverse: R,
+    ) -> M::S {
+        self.path(from, to, edge)
+            .into_iter()
+            .fold(M::ID, |acc, (l, r, reversed)| {
+                let product = segtree.product(l, r);
+                let product = if reversed { reverse(product) } else { product };
+                M::product(acc, product)
+            })
+    }
+
+    pub fn path_apply_lazy<M: Monoid, F: Morphism<M::S>>(
+        &self,
+        segtree: &mut LazySegTree<M, F>,
+        from: usize,
+        to: usize,
+        edge: bool,
+        f: F::F,
+    ) {
+        for (l, r, _) in self.path(from, to, edge) {
+            segtree.apply(l, r, f.clone());
+        }
+    }
+
+    pub fn subtree_apply_lazy<M: Monoid, F: Morphism<M::S>>(
+        &self,
+        segtree: &mut LazySegTree<M, F>,
+        node: usize,
+        edge: bool,
+        f: F::F,
+    ) {
+        let (l, r) = self.subtree(node, edge);
+        segtree.apply(l, r, f);
+    }
+}
+
+#[test]
+fn test_hld() {
+    use crate::tree::RootedTree;
+
+    struct Concat;
+
+    impl Monoid for Concat {
+        type S = (String, String);
+        const ID: Self::S = (String::new(), String::new());
+
+        fn product(lhs: Self::S, rhs: Self::S) -> Self::S {
+            (lhs.0 + &rhs.0, rhs.1 + &lhs.1)
+        }
+    }
+
+    struct Sum;
+
+    impl Monoid for Sum {
+        type S = (isize, isize);
+        const ID: Self::S = (0, 0);
+
+        fn product(lhs: Self::S, rhs: Self::S) -> Self::S {
+            (lhs.0 + rhs.0, lhs.1 + rhs.1)
+        }
+    }
+
+    struct Add;
+
+    impl Morphism<(isize, isize)> for Add {
+        type F = isize;
+        const ID: Self::F = 0;
+
+        fn composition(lhs: Self::F, rhs: Self::F) -> Self::F {
+            lhs + rhs
+        }
+
+        fn apply(f: Self::F, (sum, width): (isize, isize)) -> (isize, isize) {
+            (sum + f * width, width)
+        }
+    }
+
+    let mut graph = Graph::new(12);
+    let edges = [
+        (0, 1),
+        (0, 2),
+        (1, 3),
+        (1, 4),
+        (4, 5),
+        (4, 6),
+        (6, 7),
+        (2, 8),
+        (8, 9),
+        (8, 10),
+        (10, 11),
+    ];
+    for &(a, b) in &edges {
+        graph.edge_undirected(a, b);
+    }
+    let hld = HeavyLightDecomposition::new(&graph, 0);
+    let tree = RootedTree::new(&graph, 0);
+
+    let path_of = |from: usize, to: usize| {
+        let lca = tree.lca(from, to);
+        let mut up = vec![from];
+        while *up.last().unwrap() != lca {
+            up.push(tree.parent[*up.last().unwrap()].unwrap());
+        }
+        let mut down = vec![to];
+        while *down.last().unwrap() != lca {
+            down.push(tree.parent[*down.last().unwrap()].unwrap());
+        }
+        down.pop();
+        up.extend(down.into_iter().rev());
+        up
+    };
+
+    let names = b"abcdefghijkl";
+    let mut values = vec![(String::new(), String::new()); 12];
+    for node in 0..12 {
+        let name = (names[node] as char).to_string();
+        values[hld.index[node]] = (name.clone(), name);
+    }
+    let segtree = SegTree::<Concat>::from(values);
+    let reverse = |(forward, backward): (String, String)| (backward, forward);
+
+    for from in 0..12 {
+        for to in 0..12 {
+            assert_eq!(hld.lca(from, to), tree.lca(from, to));
+            let path = path_of(from, to);
+            let expected: String = path.iter().map(|&node| names[node] as char).collect();
+            let (forward, backward) = hld.path_product(&segtree, from, to, false, reverse);
+            assert_eq!(forward, expected);
+            assert_eq!(backward, expected.chars().rev().collect::<String>());
+            let (forward, _) = hld.path_product(&segtree, from, to, true, reverse);
+            assert_eq!(
+                forward,
+                expected.replace(names[tree.lca(from, to)] as char, "")
+            );
+        }
+    }
+    assert_eq!(hld.subtree_product(&segtree, 4, false).0.len(), 4);
+    assert_eq!(hld.subtree_product(&segtree, 4, true).0.len(), 3);
+
+    let mut segtree = LazySegTree::<Sum, Add>::from(vec![(0, 1); 12]);
+    let mut expected = [0; 12];
+    let updates = [(3, 7, 5), (11, 5, 2), (9, 9, 7), (2, 6, 1)];
+    for &(from, to, x) in &updates {
+        hld.path_apply_lazy(&mut segtree, from, to, false, x);
+        for node in path_of(from, to) {
+            expected[node] += x;
+        }
+    }
+    hld.subtree_apply_lazy(&mut segtree, 8, false, 3);
+    for &node in &[8, 9, 10, 11] {
+        expected[node] += 3;
+    }
+    for from in 0..12 {
+        for to in 0..12 {
+            let sum: isize = path_of(from, to).iter().map(|&node| expected[node]).sum();
+            let product = hld.path_product_lazy(&mut segtree, from, to, false, |x| x);
+            assert_eq!(product.0, sum);
+        }
+    }
+}
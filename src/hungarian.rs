@@ -0,0 +1,165 @@
+#![allow(clippy::needless_range_loop)]
+
+use cargo_snippet::snippet;
+
+#[snippet("hungarian")]
+fn hungarian_rows(costs: &[Vec<isize>]) -> Vec<usize> {
+    let n = costs.len();
+    let m = costs[0].len();
+    let mut u = vec![0; n + 1];
+    let mut v = vec![0; m + 1];
+    let mut p = vec![0; m + 1];
+    let mut way = vec![0; m + 1];
+
+    for i in 1..=n {
+        p[0] = i;
+        let mut j0 = 0;
+        let mut min_v = vec![isize::MAX; m + 1];
+        let mut used = vec![false; m + 1];
+
+        loop {
+            used[j0] = true;
+            let i0 = p[j0];
+            let mut delta = isize::MAX;
+            let mut j1 = 0;
+            for j in 1..=m {
+                if used[j] {
+                    continue;
+                }
+                let current = costs[i0 - 1][j - 1] - u[i0] - v[j];
+                if current < min_v[j] {
+                    min_v[j] = current;
+                    way[j] = j0;
+                }
+                if min_v[j] < delta {
+                    delta = min_v[j];
+                    j1 = j;
+                }
+            }
+            for j in 0..=m {
+                if used[j] {
+                    u[p[j]] += delta;
+                    v[j] -= delta;
+                } else {
+                    min_v[j] -= delta;
+                }
+            }
+            j0 = j1;
+            if p[j0] == 0 {
+                break;
+            }
+        }
+
+        while j0 != 0 {
+            let j1 = way[j0];
+            p[j0] = p[j1];
+            j0 = j1;
+        }
+    }
+
+    let mut assignment = vec![0; n];
+    for j in 1..=m {
+        if p[j] != 0 {
+            assignment[p[j] - 1] = j - 1;
+        }
+    }
+    assignment
+}
+
+#[snippet("hungarian")]
+pub fn hungarian(costs: &[Vec<isize>]) -> (isize, Vec<Option<usize>>) {
+    let n = costs.len();
+    let m = costs.first().map_or(0, |row| row.len());
+    if n == 0 || m == 0 {
+        return (0, vec![None; n]);
+    }
+
+    let assignment = if n <= m {
+        hungarian_rows(costs).into_iter().map(Some).collect()
+    } else {
+        let transposed: Vec<Vec<isize>> = (0..m)
+            .map(|j| (0..n).map(|i| costs[i][j]).collect())
+            .collect();
+        let mut assignment = vec![None; n];
+        for (j, i) in hungarian_rows(&transposed).into_iter().enumerate() {
+            assignment[i] = Some(j);
+        }
+        assignment
+    };
+
+    let total = assignment
+        .iter()
+        .enumerate()
+        .filter_map(|(i, &j)| j.map(|j| costs[i][j]))
+        .sum();
+    (total, assignment)
+}
+
+#[snippet("hungarian")]
+pub fn hungarian_max(costs: &[Vec<isize>]) -> (isize, Vec<Option<usize>>) {
+    let negated: Vec<Vec<isize>> = costs
+        .iter()
+        .map(|row| row.iter().map(|&cost| -cost).collect())
+        .collect();
+    let (total, assignment) = hungarian(&negated);
+    (-total, assignment)
+}
+
+#[test]
+fn test_hungarian() {
+    use crate::mincostflow::MinCostFlow;
+    use crate::random::RNG;
+
+    let costs = vec![vec![4, 1, 3], vec![2, 0, 5], vec![3, 2, 2]];
+    assert_eq!(hungarian(&costs), (5, vec![Some(1), Some(0), Some(2)]));
+    assert_eq!(hungarian_max(&costs), (11, vec![Some(0), Some(2), Some(1)]));
+    assert_eq!(hungarian(&[]), (0, vec![]));
+
+    let mut rng = RNG(98765);
+    for _ in 0..200 {
+        let n = rng.rand() as usize % 6 + 1;
+        let m = rng.rand() as usize % 6 + 1;
+        let costs: Vec<Vec<isize>> = (0..n)
+            .map(|_| (0..m).map(|_| (rng.rand() % 100) as isize).collect())
+            .collect();
+
+        for &maximize in &[false, true] {
+            let (total, assignment) = if maximize {
+                hungarian_max(&costs)
+            } else {
+                hungarian(&costs)
+            };
+
+            let mut used = vec![false; m];
+            for (i, &j) in assignment.iter().enumerate() {
+                if let Some(j) = j {
+                    assert!(!used[j]);
+                    used[j] = true;
+                    assert!(i < n);
+                }
+            }
+            assert_eq!(assignment.iter().flatten().count(), n.min(m));
+
+            let mut flow = MinCostFlow::new(n + m + 2);
+            let (source, sink) = (n + m, n + m + 1);
+            for i in 0..n {
+                flow.add_edge(source, i, 1, 0);
+                for j in 0..m {
+                    let cost = if maximize {
+                        100 - costs[i][j]
+                    } else {
+                        costs[i][j]
+                    };
+                    flow.add_edge(i, n + j, 1, cost);
+                }
+            }
+            for j in 0..m {
+                flow.add_edge(n + j, sink, 1, 0);
+            }
+            let (amount, cost) = flow.min_cost_max_flow(source, sink);
+            assert_eq!(amount as usize, n.min(m));
+            let expected = if maximize { 100 * amount - cost } else { cost };
+            assert_eq!(total, expected);
+        }
+    }
+}
@@ -1,12 +1,17 @@
 #![allow(dead_code)]
 #![allow(unused_macros)]
 
+mod bipartite;
 mod bitree;
 mod bits;
 mod bound;
+mod centroid;
+mod csr;
 mod geometric;
 mod graph;
 mod grundy;
+mod hld;
+mod hungarian;
 mod levenshtein;
 mod maxflow;
 mod memoize;
@@ -14,10 +19,14 @@ mod mincostflow;
 mod modint;
 mod multiset;
 mod produce;
+mod push_relabel;
 mod random;
 mod range_union;
+mod rerooting;
 mod rolling_hash;
 mod safe_index;
 mod segtree;
 mod subseqs;
+mod tree;
+mod two_sat;
 mod union_find;
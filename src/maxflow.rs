@@ -1,12 +1,14 @@
+use crate::csr::Csr;
+use crate::graph::Graph;
 use cargo_snippet::snippet;
 
 #[snippet("maxflow")]
 #[derive(Copy, Clone, Debug, PartialEq)]
-struct Edge {
-    from: usize,
-    to: usize,
-    flow: isize,
-    capacity: isize,
+pub(crate) struct Edge {
+    pub(crate) from: usize,
+    pub(crate) to: usize,
+    pub(crate) flow: isize,
+    pub(crate) capacity: isize,
 }
 
 #[snippet("maxflow")]
@@ -23,10 +25,10 @@ impl Edge {
 
 #[snippet("maxflow")]
 #[derive(Copy, Clone, Debug, PartialEq)]
-struct EdgeInternal {
-    to: usize,
-    capacity: isize,
-    rev_ref: usize,
+pub(crate) struct EdgeInternal {
+    pub(crate) to: usize,
+    pub(crate) capacity: isize,
+    pub(crate) rev_ref: usize,
 }
 
 #[snippet("maxflow")]
@@ -41,10 +43,12 @@ impl EdgeInternal {
 }
 
 #[snippet("maxflow")]
-struct MaxFlow {
-    nodes: usize,
-    edges: Vec<Vec<EdgeInternal>>,
+pub(crate) struct MaxFlow {
+    pub(crate) nodes: usize,
+    pub(crate) edges: Vec<Vec<EdgeInternal>>,
     edge_indices: Vec<(usize, usize)>,
+    lowers: Vec<isize>,
+    excess: Vec<isize>,
 }
 
 #[snippet("maxflow")]
@@ -56,11 +60,13 @@ fn fill<T: Clone>(slice: &mut [T], value: T) {
 
 #[snippet("maxflow")]
 impl MaxFlow {
-    fn new(nodes: usize) -> Self {
+    pub(crate) fn new(nodes: usize) -> Self {
         MaxFlow {
             nodes,
             edges: vec![Vec::new(); nodes],
             edge_indices: Vec::new(),
+            lowers: Vec::new(),
+            excess: vec![0; nodes],
         }
     }
 
@@ -76,23 +82,62 @@ impl MaxFlow {
         Edge::new(from, to, flow, capacity)
     }
 
-    fn add_edge(&mut self, from: usize, to: usize, capacity: isize) {
+    pub(crate) fn add_edge(&mut self, from: usize, to: usize, capacity: isize) -> usize {
         assert!(from < self.nodes);
         assert!(to < self.nodes);
 
         let edge_ref = self.edges[from].len();
-        let edge_rev_ref = self.edges[to].len();
+        let edge_rev_ref = self.edges[to].len() + (from == to) as usize;
         self.edge_indices.push((from, edge_ref));
 
         self.edges[from].push(EdgeInternal::new(to, capacity, edge_rev_ref));
         self.edges[to].push(EdgeInternal::new(from, 0, edge_ref));
+        self.lowers.push(0);
+        self.edge_indices.len() - 1
     }
 
-    fn edges(&self) -> Vec<Edge> {
-        self.edge_indices
-            .iter()
-            .map(|&idx| self.to_external_edge(idx))
-            .collect()
+    pub(crate) fn add_edge_with_bounds(
+        &mut self,
+        from: usize,
+        to: usize,
+        lower: isize,
+        upper: isize,
+    ) -> usize {
+        assert!(0 <= lower && lower <= upper);
+
+        let edge_id = self.add_edge(from, to, upper - lower);
+        self.lowers[edge_id] = lower;
+        self.excess[from] -= lower;
+        self.excess[to] += lower;
+        edge_id
+    }
+
+    pub(crate) fn change_edge(&mut self, edge_id: usize, new_capacity: isize, new_flow: isize) {
+        let lower = self.lowers[edge_id];
+        assert!(lower <= new_flow && new_flow <= new_capacity);
+
+        let (from, edge_ref) = self.edge_indices[edge_id];
+        let EdgeInternal { to, rev_ref, .. } = self.edges[from][edge_ref];
+        self.edges[from][edge_ref].capacity = new_capacity - new_flow;
+        self.edges[to][rev_ref].capacity = new_flow - lower;
+    }
+
+    fn pop_edge(&mut self) {
+        let (from, edge_ref) = self.edge_indices.pop().unwrap();
+        let to = self.edges[from][edge_ref].to;
+        self.lowers.pop();
+        self.edges[from].pop();
+        self.edges[to].pop();
+    }
+
+    pub(crate) fn edge(&self, edge_id: usize) -> Edge {
+        let edge = self.to_external_edge(self.edge_indices[edge_id]);
+        let lower = self.lowers[edge_id];
+        Edge::new(edge.from, edge.to, edge.flow + lower, edge.capacity + lower)
+    }
+
+    pub(crate) fn edges(&self) -> Vec<Edge> {
+        (0..self.edge_indices.len()).map(|i| self.edge(i)).collect()
     }
 
     fn levels(&self, levels: &mut [Option<usize>], node: usize) {
@@ -116,49 +161,50 @@ impl MaxFlow {
 
     fn flow(
         &mut self,
-        node: usize,
+        source: usize,
         sink: usize,
         limit: isize,
         checked: &mut [usize],
         levels: &[Option<usize>],
     ) -> isize {
-        if node == sink || limit == 0 {
-            return limit;
-        }
-
-        let mut flow = 0;
+        let mut path: Vec<(usize, usize)> = Vec::new();
+        let mut node = source;
 
-        for i in checked[node].. {
-            let edge = if let Some(edge) = self.edges[node].get(i) {
-                *edge
-            } else {
-                break;
-            };
-            checked[node] += 1;
-
-            let dest_is_far = levels[node].and_then(|from| levels[edge.to].map(|to| from < to));
+        loop {
+            if node == sink {
+                let flow = path
+                    .iter()
+                    .map(|&(node, i)| self.edges[node][i].capacity)
+                    .fold(limit, isize::min);
+
+                for &(node, i) in &path {
+                    let EdgeInternal { to, rev_ref, .. } = self.edges[node][i];
+                    self.edges[node][i].capacity -= flow;
+                    self.edges[to][rev_ref].capacity += flow;
+                }
 
-            if !dest_is_far.unwrap_or(false) {
-                continue;
+                return flow;
             }
 
-            let f = self.flow(
-                edge.to,
-                sink,
-                isize::min(limit - flow, edge.capacity),
-                checked,
-                levels,
-            );
+            if let Some(edge) = self.edges[node].get(checked[node]) {
+                let dest_is_far = levels[node].and_then(|from| levels[edge.to].map(|to| from < to));
 
-            self.edges[node][i].capacity -= f;
-            self.edges[edge.to][edge.rev_ref].capacity += f;
-            flow += f;
+                if edge.capacity > 0 && dest_is_far.unwrap_or(false) {
+                    path.push((node, checked[node]));
+                    node = edge.to;
+                } else {
+                    checked[node] += 1;
+                }
+            } else if let Some((prev, _)) = path.pop() {
+                checked[prev] += 1;
+                node = prev;
+            } else {
+                return 0;
+            }
         }
-
-        flow
     }
 
-    fn max_flow_limited(&mut self, source: usize, sink: usize, limit: isize) -> isize {
+    pub(crate) fn max_flow_limited(&mut self, source: usize, sink: usize, limit: isize) -> isize {
         let mut flow = 0;
         let mut levels = vec![None; self.nodes];
         let mut checked = vec![0; self.nodes];
@@ -166,14 +212,14 @@ impl MaxFlow {
         loop {
             self.levels(&mut levels, source);
 
-            if levels[sink].is_none() {
+            if levels[sink].is_none() || flow >= limit {
                 break flow;
             }
 
             fill(&mut checked, 0);
 
             loop {
-                let f = self.flow(source, sink, limit, &mut checked, &levels);
+                let f = self.flow(source, sink, limit - flow, &mut checked, &levels);
                 if f <= 0 {
                     break;
                 }
@@ -183,11 +229,66 @@ impl MaxFlow {
         }
     }
 
-    fn max_flow(&mut self, source: usize, sink: usize) -> isize {
+    pub(crate) fn max_flow(&mut self, source: usize, sink: usize) -> isize {
         self.max_flow_limited(source, sink, std::isize::MAX)
     }
 
-    fn min_cut(&self, s: usize) -> Vec<bool> {
+    pub(crate) fn feasible_circulation(&mut self) -> bool {
+        let mut excess = self.excess.clone();
+        for &edge_index in &self.edge_indices {
+            let edge = self.to_external_edge(edge_index);
+            excess[edge.from] -= edge.flow;
+            excess[edge.to] += edge.flow;
+        }
+
+        let saved = self.edges.clone();
+        let (source, sink) = (self.nodes, self.nodes + 1);
+        self.nodes += 2;
+        self.edges.resize(self.nodes, Vec::new());
+
+        let mut required = 0;
+        let mut added = 0;
+        for (node, &excess) in excess.iter().enumerate() {
+            if excess > 0 {
+                self.add_edge(source, node, excess);
+                required += excess;
+                added += 1;
+            } else if excess < 0 {
+                self.add_edge(node, sink, -excess);
+                added += 1;
+            }
+        }
+
+        let flow = self.max_flow(source, sink);
+
+        for _ in 0..added {
+            self.pop_edge();
+        }
+        self.nodes -= 2;
+        self.edges.truncate(self.nodes);
+
+        if flow != required {
+            self.edges = saved;
+        }
+        flow == required
+    }
+
+    pub(crate) fn max_flow_with_bounds(&mut self, source: usize, sink: usize) -> Option<isize> {
+        let backward = self.add_edge(sink, source, isize::MAX);
+        let forward = self.add_edge(source, sink, isize::MAX);
+        let feasible = self.feasible_circulation();
+        let base = self.edge(backward).flow - self.edge(forward).flow;
+        self.pop_edge();
+        self.pop_edge();
+
+        if feasible {
+            Some(base + self.max_flow(source, sink))
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn min_cut(&self, s: usize) -> Vec<bool> {
         let mut visited = vec![false; self.nodes];
         let mut queue = std::collections::VecDeque::new();
         queue.push_back(s);
@@ -208,6 +309,74 @@ impl MaxFlow {
     }
 }
 
+#[snippet(name = "gomory_hu", include = "maxflow, graph")]
+pub(crate) fn gomory_hu(graph: &Graph) -> Graph {
+    let nodes = graph.nodes;
+    let mut parent = vec![0; nodes];
+    let mut tree = Graph::new(nodes);
+
+    for node in 1..nodes {
+        let mut network = MaxFlow::new(nodes);
+        for from in 0..nodes {
+            for edge in &graph.edges[from] {
+                network.add_edge(from, edge.node_to, edge.cost);
+            }
+        }
+
+        let cut = network.max_flow(node, parent[node]);
+        let side = network.min_cut(node);
+        for other in node + 1..nodes {
+            if side[other] && parent[other] == parent[node] {
+                parent[other] = node;
+            }
+        }
+        tree.edge_undirected_costed(node, parent[node], cut);
+    }
+
+    tree
+}
+
+#[snippet(name = "maxflow_dot", include = "maxflow")]
+impl MaxFlow {
+    pub(crate) fn to_dot(&self, source: usize) -> String {
+        let min_cut = self.min_cut(source);
+
+        let mut dot = String::from("digraph {\n");
+        for (node, &side) in min_cut.iter().enumerate() {
+            if side {
+                dot.push_str(&format!(
+                    "    {} [style=filled, fillcolor=lightblue];\n",
+                    node
+                ));
+            } else {
+                dot.push_str(&format!("    {};\n", node));
+            }
+        }
+        for edge in self.edges() {
+            dot.push_str(&format!(
+                "    {} -> {} [label=\"{}/{}\"];\n",
+                edge.from, edge.to, edge.flow, edge.capacity
+            ));
+        }
+        dot.push_str("}\n");
+
+        dot
+    }
+}
+
+#[snippet(name = "maxflow_csr", include = "maxflow, csr")]
+impl MaxFlow {
+    // Residual edges are mutated while flowing, so the CSR is copied into adjacency lists.
+    pub(crate) fn from_csr(csr: &Csr<isize>) -> Self {
+        let mut network = MaxFlow::new(csr.nodes);
+        for (from, edge) in csr.edges_by_id() {
+            network.add_edge(from, edge.to, edge.weight);
+        }
+
+        network
+    }
+}
+
 #[test]
 fn test_maxflow() {
     let edges = [
@@ -240,3 +409,209 @@ fn test_maxflow() {
         .sum();
     assert_eq!(min_cut_flow, 10);
 }
+
+#[test]
+fn test_maxflow_with_bounds() {
+    fn check_bounds(graph: &MaxFlow, bounds: &[(usize, usize, isize, isize)]) {
+        let mut balance = vec![0; graph.nodes];
+        for (edge, &(from, to, lower, upper)) in graph.edges().iter().zip(bounds) {
+            assert_eq!((edge.from, edge.to, edge.capacity), (from, to, upper));
+            assert!(lower <= edge.flow && edge.flow <= upper);
+            balance[from] -= edge.flow;
+            balance[to] += edge.flow;
+        }
+        assert!(balance.iter().all(|&b| b == 0));
+    }
+
+    let bounds = [(0, 1, 2, 5), (1, 2, 1, 4), (2, 0, 3, 3)];
+    let mut graph = MaxFlow::new(3);
+    for &(from, to, lower, upper) in &bounds {
+        graph.add_edge_with_bounds(from, to, lower, upper);
+    }
+    assert!(graph.feasible_circulation());
+    check_bounds(&graph, &bounds);
+    assert!(graph.edges().iter().all(|e| e.flow == 3));
+    assert!(graph.feasible_circulation());
+    check_bounds(&graph, &bounds);
+    assert!(graph.edges().iter().all(|e| e.flow == 3));
+
+    let mut graph = MaxFlow::new(3);
+    graph.add_edge_with_bounds(0, 1, 2, 5);
+    graph.add_edge_with_bounds(1, 2, 1, 4);
+    graph.add_edge_with_bounds(2, 0, 0, 1);
+    assert!(!graph.feasible_circulation());
+    let flows: Vec<_> = graph.edges().iter().map(|e| e.flow).collect();
+    assert_eq!(flows, [2, 1, 0]);
+
+    let bounds = [
+        (0, 1, 1, 3),
+        (0, 2, 0, 2),
+        (1, 2, 2, 4),
+        (1, 3, 0, 2),
+        (2, 3, 1, 3),
+    ];
+    let mut graph = MaxFlow::new(4);
+    for &(from, to, lower, upper) in &bounds {
+        graph.add_edge_with_bounds(from, to, lower, upper);
+    }
+    assert_eq!(graph.max_flow_with_bounds(0, 3), Some(4));
+    let edges = graph.edges();
+    let flow_from_source: isize = edges.iter().filter(|e| e.from == 0).map(|e| e.flow).sum();
+    assert_eq!(flow_from_source, 4);
+    let mut balance = vec![0; 4];
+    for (edge, &(_, _, lower, upper)) in edges.iter().zip(&bounds) {
+        assert!(lower <= edge.flow && edge.flow <= upper);
+        balance[edge.from] -= edge.flow;
+        balance[edge.to] += edge.flow;
+    }
+    assert_eq!(balance, vec![-4, 0, 0, 4]);
+
+    let mut graph = MaxFlow::new(4);
+    graph.add_edge_with_bounds(0, 1, 1, 3);
+    graph.add_edge_with_bounds(1, 2, 4, 4);
+    graph.add_edge_with_bounds(2, 3, 0, 5);
+    assert_eq!(graph.max_flow_with_bounds(0, 3), None);
+
+    let mut graph = MaxFlow::new(2);
+    graph.add_edge_with_bounds(1, 0, 2, 4);
+    assert_eq!(graph.max_flow_with_bounds(0, 1), Some(-2));
+    assert_eq!(graph.edge(0).flow, 2);
+    assert_eq!(graph.edges().len(), 1);
+
+    let mut graph = MaxFlow::new(3);
+    graph.add_edge_with_bounds(0, 1, 1, 3);
+    graph.add_edge_with_bounds(1, 2, 0, 5);
+    graph.add_edge_with_bounds(2, 0, 4, 6);
+    assert_eq!(graph.max_flow_with_bounds(0, 2), Some(-1));
+}
+
+#[test]
+fn test_maxflow_change_edge() {
+    let mut graph = MaxFlow::new(4);
+    let e0 = graph.add_edge(0, 1, 5);
+    let e1 = graph.add_edge(0, 2, 4);
+    let e2 = graph.add_edge(1, 3, 3);
+    let e3 = graph.add_edge(2, 3, 2);
+    let e4 = graph.add_edge(1, 2, 3);
+    assert_eq!((e0, e1, e2, e3, e4), (0, 1, 2, 3, 4));
+
+    assert_eq!(graph.max_flow(0, 3), 5);
+    assert_eq!(graph.edge(e2), Edge::new(1, 3, 3, 3));
+    assert_eq!(graph.edge(e3), Edge::new(2, 3, 2, 2));
+
+    let flow = graph.edge(e3).flow;
+    graph.change_edge(e3, 6, flow);
+    assert_eq!(graph.max_flow(0, 3), 4);
+    assert_eq!(graph.edge(e3), Edge::new(2, 3, 6, 6));
+
+    for (i, &capacity) in [5, 4, 1, 6, 3].iter().enumerate() {
+        graph.change_edge(i, capacity, 0);
+    }
+    assert_eq!(graph.max_flow(0, 3), 7);
+    assert_eq!(graph.edge(e2).flow, 1);
+    assert_eq!(graph.edge(e3).flow, 6);
+}
+
+#[test]
+fn test_maxflow_self_loop() {
+    let mut graph = MaxFlow::new(3);
+    graph.add_edge(0, 1, 4);
+    let e1 = graph.add_edge(1, 1, 5);
+    graph.add_edge(1, 2, 3);
+    assert_eq!(graph.edge(e1), Edge::new(1, 1, 0, 5));
+
+    assert_eq!(graph.max_flow(0, 2), 3);
+    assert_eq!(graph.edge(e1), Edge::new(1, 1, 0, 5));
+
+    graph.change_edge(e1, 5, 2);
+    assert_eq!(graph.edge(e1), Edge::new(1, 1, 2, 5));
+    assert!(graph.to_dot(0).contains("1 -> 1 [label=\"2/5\"];"));
+}
+
+#[test]
+fn test_maxflow_limited() {
+    let mut graph = MaxFlow::new(4);
+    graph.add_edge(0, 1, 5);
+    graph.add_edge(0, 2, 5);
+    graph.add_edge(1, 3, 5);
+    graph.add_edge(2, 3, 5);
+    assert_eq!(graph.max_flow_limited(0, 3, 7), 7);
+    assert_eq!(graph.max_flow(0, 3), 3);
+
+    let nodes = 100000;
+    let mut graph = MaxFlow::new(nodes);
+    for node in 1..nodes {
+        graph.add_edge(node - 1, node, 1);
+    }
+    assert_eq!(graph.max_flow(0, nodes - 1), 1);
+}
+
+#[test]
+fn test_gomory_hu() {
+    use crate::random::RNG;
+
+    fn path_min(tree: &Graph, from: usize, to: usize) -> Option<isize> {
+        let mut stack = vec![(from, usize::MAX, isize::MAX)];
+        while let Some((node, prev, min)) = stack.pop() {
+            if node == to {
+                return Some(min);
+            }
+            for edge in &tree.edges[node] {
+                if edge.node_to != prev {
+                    stack.push((edge.node_to, node, min.min(edge.cost)));
+                }
+            }
+        }
+        None
+    }
+
+    let mut rng = RNG(24680);
+    for _ in 0..100 {
+        let nodes = rng.rand() as usize % 7 + 2;
+        let edge_count = rng.rand() as usize % 15;
+        let mut graph = Graph::new(nodes);
+        for _ in 0..edge_count {
+            let a = rng.rand() as usize % nodes;
+            let b = rng.rand() as usize % nodes;
+            let cost = (rng.rand() % 10) as isize;
+            graph.edge_undirected_costed(a, b, cost);
+        }
+
+        let tree = gomory_hu(&graph);
+        assert_eq!(tree.edge_count, nodes - 1);
+        for from in 0..nodes {
+            for to in from + 1..nodes {
+                let mut network = MaxFlow::new(nodes);
+                for node in 0..nodes {
+                    for edge in &graph.edges[node] {
+                        network.add_edge(node, edge.node_to, edge.cost);
+                    }
+                }
+                let expected = network.max_flow(from, to);
+                assert_eq!(path_min(&tree, from, to), Some(expected));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_maxflow_to_dot() {
+    let mut graph = MaxFlow::new(4);
+    graph.add_edge(0, 1, 3);
+    graph.add_edge(0, 2, 2);
+    graph.add_edge(1, 3, 1);
+    graph.add_edge(2, 3, 4);
+    assert_eq!(graph.max_flow(0, 3), 3);
+
+    assert_eq!(
+        graph.to_dot(0),
+        "digraph {\n    \
+         0 [style=filled, fillcolor=lightblue];\n    \
+         1 [style=filled, fillcolor=lightblue];\n    \
+         2;\n    3;\n    \
+         0 -> 1 [label=\"1/3\"];\n    \
+         0 -> 2 [label=\"2/2\"];\n    \
+         1 -> 3 [label=\"1/1\"];\n    \
+         2 -> 3 [label=\"2/4\"];\n}\n"
+    );
+}
@@ -1,8 +1,9 @@
+use crate::csr::Csr;
 use cargo_snippet::snippet;
 
 #[snippet("mincostflow")]
 #[derive(Copy, Clone, Debug, PartialEq)]
-struct Edge {
+pub(crate) struct Edge {
     from: usize,
     to: usize,
     flow: isize,
@@ -45,10 +46,19 @@ impl EdgeInternal {
 }
 
 #[snippet("mincostflow")]
-struct MinCostFlow {
+pub(crate) struct MinCostFlow {
     nodes: usize,
     edges: Vec<Vec<EdgeInternal>>,
     edge_indices: Vec<(usize, usize)>,
+    supplies: Vec<isize>,
+}
+
+#[snippet("mincostflow")]
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct BFlow {
+    pub(crate) cost: isize,
+    pub(crate) flows: Vec<isize>,
+    pub(crate) potentials: Vec<isize>,
 }
 
 #[snippet("mincostflow")]
@@ -60,11 +70,12 @@ fn fill<T: Clone>(slice: &mut [T], value: T) {
 
 #[snippet("mincostflow")]
 impl MinCostFlow {
-    fn new(nodes: usize) -> Self {
+    pub(crate) fn new(nodes: usize) -> Self {
         MinCostFlow {
             nodes,
             edges: vec![Vec::new(); nodes],
             edge_indices: Vec::new(),
+            supplies: vec![0; nodes],
         }
     }
 
@@ -81,19 +92,34 @@ impl MinCostFlow {
         Edge::new(from, to, flow, capacity, cost)
     }
 
-    fn add_edge(&mut self, from: usize, to: usize, capacity: isize, cost: isize) {
+    pub(crate) fn add_edge(&mut self, from: usize, to: usize, capacity: isize, cost: isize) {
         assert!(from < self.nodes);
         assert!(to < self.nodes);
 
         let edge_ref = self.edges[from].len();
-        let edge_rev_ref = self.edges[to].len();
+        let edge_rev_ref = self.edges[to].len() + (from == to) as usize;
         self.edge_indices.push((from, edge_ref));
 
         self.edges[from].push(EdgeInternal::new(to, capacity, cost, edge_rev_ref));
         self.edges[to].push(EdgeInternal::new(from, 0, -cost, edge_ref));
     }
 
-    fn edges(&self) -> Vec<Edge> {
+    pub(crate) fn add_supply(&mut self, node: usize, amount: isize) {
+        self.supplies[node] += amount;
+    }
+
+    pub(crate) fn add_demand(&mut self, node: usize, amount: isize) {
+        self.supplies[node] -= amount;
+    }
+
+    fn pop_edge(&mut self) {
+        let (from, edge_ref) = self.edge_indices.pop().unwrap();
+        let to = self.edges[from][edge_ref].to;
+        self.edges[from].pop();
+        self.edges[to].pop();
+    }
+
+    pub(crate) fn edges(&self) -> Vec<Edge> {
         self.edge_indices
             .iter()
             .map(|&idx| self.to_external_edge(idx))
@@ -130,6 +156,45 @@ impl MinCostFlow {
         }
     }
 
+    fn bellman_ford(&self, mut dist: Vec<Option<isize>>) -> Vec<isize> {
+        for iteration in 0..self.nodes {
+            let mut updated = false;
+            for node in 0..self.nodes {
+                let d = match dist[node] {
+                    Some(d) => d,
+                    None => continue,
+                };
+                for edge in self.edges[node].iter().filter(|e| e.capacity > 0) {
+                    if dist[edge.to].is_none() || d + edge.cost < dist[edge.to].unwrap() {
+                        dist[edge.to] = Some(d + edge.cost);
+                        updated = true;
+                    }
+                }
+            }
+            if !updated {
+                break;
+            }
+            assert!(iteration + 1 < self.nodes, "negative cycle");
+        }
+
+        dist.into_iter().map(|d| d.unwrap_or(0)).collect()
+    }
+
+    fn initial_potential(&self, source: usize) -> Vec<isize> {
+        let negative = self
+            .edges
+            .iter()
+            .flatten()
+            .any(|e| e.capacity > 0 && e.cost < 0);
+        if !negative {
+            return vec![0; self.nodes];
+        }
+
+        let mut dist = vec![None; self.nodes];
+        dist[source] = Some(0);
+        self.bellman_ford(dist)
+    }
+
     fn flow(
         &mut self,
         source: usize,
@@ -163,34 +228,190 @@ impl MinCostFlow {
         Some((flow, cost + flow * cost_per_flow))
     }
 
-    fn min_cost_max_flow_limited(
+    pub(crate) fn slope(
         &mut self,
         source: usize,
         sink: usize,
         limit: isize,
-    ) -> (isize, isize) {
-        let mut flow = 0;
-        let mut cost = 0;
+    ) -> Vec<(isize, isize)> {
+        let mut slope = vec![(0, 0)];
         let mut path = vec![None; self.nodes];
-        let mut potential = vec![0; self.nodes];
+        let mut potential = self.initial_potential(source);
 
         loop {
             self.path(&mut potential, &mut path, source);
 
+            let (flow, cost) = *slope.last().unwrap();
             if let Some((f, c)) = self.flow(source, sink, limit - flow, &path) {
-                flow += f;
-                cost += c;
+                if slope.len() >= 2 {
+                    let (prev_flow, prev_cost) = slope[slope.len() - 2];
+                    if (cost - prev_cost) * f == c * (flow - prev_flow) {
+                        slope.pop();
+                    }
+                }
+                slope.push((flow + f, cost + c));
             } else {
                 break;
             }
         }
 
-        (flow, cost)
+        slope
+    }
+
+    pub(crate) fn min_cost_max_flow_limited(
+        &mut self,
+        source: usize,
+        sink: usize,
+        limit: isize,
+    ) -> (isize, isize) {
+        *self.slope(source, sink, limit).last().unwrap()
     }
 
-    fn min_cost_max_flow(&mut self, source: usize, sink: usize) -> (isize, isize) {
+    pub(crate) fn min_cost_max_flow(&mut self, source: usize, sink: usize) -> (isize, isize) {
         self.min_cost_max_flow_limited(source, sink, std::isize::MAX)
     }
+
+    pub(crate) fn min_cut(&self, s: usize) -> Vec<bool> {
+        let mut visited = vec![false; self.nodes];
+        let mut queue = std::collections::VecDeque::new();
+        queue.push_back(s);
+        visited[s] = true;
+
+        while let Some(node) = queue.pop_front() {
+            for edge in &self.edges[node] {
+                if visited[edge.to] || edge.capacity <= 0 {
+                    continue;
+                }
+
+                queue.push_back(edge.to);
+                visited[edge.to] = true;
+            }
+        }
+
+        visited
+    }
+
+    fn min_cost_b_flow(&mut self, mut excess: Vec<isize>) -> Option<isize> {
+        let saved = self.edges.clone();
+        let mut cost = 0;
+        for node in 0..self.nodes {
+            for i in 0..self.edges[node].len() {
+                let EdgeInternal {
+                    to,
+                    capacity,
+                    cost: cost_per_flow,
+                    rev_ref,
+                } = self.edges[node][i];
+                if capacity > 0 && cost_per_flow < 0 {
+                    self.edges[node][i].capacity = 0;
+                    self.edges[to][rev_ref].capacity += capacity;
+                    excess[node] -= capacity;
+                    excess[to] += capacity;
+                    cost += capacity * cost_per_flow;
+                }
+            }
+        }
+
+        let (source, sink) = (self.nodes, self.nodes + 1);
+        self.nodes += 2;
+        self.edges.resize(self.nodes, Vec::new());
+
+        let mut supply = 0;
+        let mut demand = 0;
+        let mut added = 0;
+        for (node, &excess) in excess.iter().enumerate() {
+            if excess > 0 {
+                self.add_edge(source, node, excess, 0);
+                supply += excess;
+                added += 1;
+            } else if excess < 0 {
+                self.add_edge(node, sink, -excess, 0);
+                demand -= excess;
+                added += 1;
+            }
+        }
+
+        let (flow, c) = self.min_cost_max_flow(source, sink);
+        cost += c;
+
+        for _ in 0..added {
+            self.pop_edge();
+        }
+        self.nodes -= 2;
+        self.edges.truncate(self.nodes);
+
+        if flow == supply && flow == demand {
+            Some(cost)
+        } else {
+            self.edges = saved;
+            None
+        }
+    }
+
+    pub(crate) fn min_cost_circulation(&mut self) -> isize {
+        self.min_cost_b_flow(vec![0; self.nodes]).unwrap()
+    }
+
+    pub(crate) fn solve(&mut self) -> Option<BFlow> {
+        let mut excess = self.supplies.clone();
+        for edge in self.edges() {
+            excess[edge.from] -= edge.flow;
+            excess[edge.to] += edge.flow;
+        }
+        self.min_cost_b_flow(excess)?;
+
+        let edges = self.edges();
+        let cost = edges.iter().map(|e| e.flow * e.cost).sum();
+        let flows = edges.iter().map(|e| e.flow).collect();
+        let potentials = self.bellman_ford(vec![Some(0); self.nodes]);
+
+        Some(BFlow {
+            cost,
+            flows,
+            potentials,
+        })
+    }
+}
+
+#[snippet(name = "mincostflow_dot", include = "mincostflow")]
+impl MinCostFlow {
+    pub(crate) fn to_dot(&self, source: usize) -> String {
+        let min_cut = self.min_cut(source);
+
+        let mut dot = String::from("digraph {\n");
+        for (node, &side) in min_cut.iter().enumerate() {
+            if side {
+                dot.push_str(&format!(
+                    "    {} [style=filled, fillcolor=lightblue];\n",
+                    node
+                ));
+            } else {
+                dot.push_str(&format!("    {};\n", node));
+            }
+        }
+        for edge in self.edges() {
+            dot.push_str(&format!(
+                "    {} -> {} [label=\"{}/{}, {}\"];\n",
+                edge.from, edge.to, edge.flow, edge.capacity, edge.cost
+            ));
+        }
+        dot.push_str("}\n");
+
+        dot
+    }
+}
+
+#[snippet(name = "mincostflow_csr", include = "mincostflow, csr")]
+impl MinCostFlow {
+    pub(crate) fn from_csr(csr: &Csr<(isize, isize)>) -> Self {
+        let mut network = MinCostFlow::new(csr.nodes);
+        for (from, edge) in csr.edges_by_id() {
+            let (capacity, cost) = edge.weight;
+            network.add_edge(from, edge.to, capacity, cost);
+        }
+
+        network
+    }
 }
 
 #[test]
@@ -254,5 +475,181 @@ fn test_mincostflow() {
     assert_eq!(flow_to_sink, 6);
     let cost_sum: isize = edges.iter().map(|e| e.flow * e.cost).sum();
     assert_eq!(cost_sum, 58);
+}
 
+#[test]
+fn test_mincostflow_slope() {
+    let edges = [
+        (0, 1, 8, 3),
+        (0, 2, 5, 2),
+        (1, 2, 9, 7),
+        (1, 3, 4, 3),
+        (2, 3, 3, 4),
+        (2, 4, 9, 2),
+        (3, 4, 12, 9),
+        (3, 5, 9, 6),
+        (4, 5, 2, 1),
+    ];
+
+    let mut graph = MinCostFlow::new(6);
+    for &(from, to, capacity, cost) in &edges {
+        graph.add_edge(from, to, capacity, cost);
+    }
+    let slope = graph.slope(0, 5, isize::MAX);
+    assert_eq!(slope.first(), Some(&(0, 0)));
+    assert_eq!(slope.last(), Some(&(9, 94)));
+    for window in slope.windows(3) {
+        let (f0, c0) = window[0];
+        let (f1, c1) = window[1];
+        let (f2, c2) = window[2];
+        assert!((c1 - c0) * (f2 - f1) < (c2 - c1) * (f1 - f0));
+    }
+
+    let mut graph = MinCostFlow::new(4);
+    graph.add_edge(0, 1, 2, 1);
+    graph.add_edge(0, 2, 3, 1);
+    graph.add_edge(1, 3, 2, 0);
+    graph.add_edge(2, 3, 3, 0);
+    graph.add_edge(0, 3, 4, 5);
+    assert_eq!(graph.slope(0, 3, 100), [(0, 0), (5, 5), (9, 25)]);
+    assert_eq!(graph.slope(0, 3, 100), [(0, 0)]);
+
+    let mut graph = MinCostFlow::new(4);
+    graph.add_edge(0, 1, 2, 1);
+    graph.add_edge(0, 2, 3, 1);
+    graph.add_edge(1, 3, 2, 0);
+    graph.add_edge(2, 3, 3, 0);
+    graph.add_edge(0, 3, 4, 5);
+    assert_eq!(graph.slope(0, 3, 7), [(0, 0), (5, 5), (7, 15)]);
+}
+
+#[test]
+fn test_mincostflow_negative_costs() {
+    let mut graph = MinCostFlow::new(4);
+    graph.add_edge(0, 1, 2, 4);
+    graph.add_edge(0, 2, 2, 1);
+    graph.add_edge(1, 2, 1, -5);
+    graph.add_edge(1, 3, 2, 1);
+    graph.add_edge(2, 3, 3, 2);
+    assert_eq!(graph.slope(0, 3, 100), [(0, 0), (1, 1), (3, 7), (4, 12)]);
+
+    let mut graph = MinCostFlow::new(3);
+    graph.add_edge(0, 1, 3, -2);
+    graph.add_edge(1, 2, 2, -3);
+    graph.add_edge(0, 2, 5, -1);
+    assert_eq!(graph.min_cost_max_flow(0, 2), (7, -15));
+
+    let mut graph = MinCostFlow::new(4);
+    graph.add_edge(0, 1, 4, 2);
+    graph.add_edge(1, 2, 3, -5);
+    graph.add_edge(2, 0, 5, 1);
+    graph.add_edge(2, 3, 2, -1);
+    graph.add_edge(3, 1, 2, 1);
+    graph.add_edge(3, 3, 1, -2);
+    graph.add_supply(0, 100);
+    assert_eq!(graph.min_cost_circulation(), -14);
+    let edges = graph.edges();
+    let mut balance = [0; 4];
+    for edge in &edges {
+        assert!(0 <= edge.flow && edge.flow <= edge.capacity);
+        balance[edge.from] -= edge.flow;
+        balance[edge.to] += edge.flow;
+    }
+    assert_eq!(balance, [0; 4]);
+    let cost: isize = edges.iter().map(|e| e.flow * e.cost).sum();
+    assert_eq!(cost, -14);
+    assert_eq!(graph.edges().len(), 6);
+}
+
+#[test]
+fn test_mincostflow_b_flow() {
+    let edges = [
+        (0, 3, 4, 2),
+        (0, 4, 5, 6),
+        (1, 3, 3, 5),
+        (1, 4, 6, 1),
+        (2, 3, 5, 3),
+        (2, 4, 2, -1),
+        (3, 4, 2, 1),
+    ];
+    let mut graph = MinCostFlow::new(5);
+    for &(from, to, capacity, cost) in &edges {
+        graph.add_edge(from, to, capacity, cost);
+    }
+    graph.add_supply(0, 4);
+    graph.add_supply(1, 5);
+    graph.add_supply(2, 3);
+    graph.add_demand(3, 6);
+    graph.add_demand(4, 6);
+
+    let result = graph.solve().unwrap();
+    assert_eq!(graph.solve(), Some(result.clone()));
+    assert_eq!(result.cost, 18);
+    assert_eq!(result.flows, [4, 0, 1, 4, 1, 2, 0]);
+
+    let mut balance = [4, 5, 3, -6, -6];
+    for (&(from, to, capacity, cost), &flow) in edges.iter().zip(&result.flows) {
+        assert!(0 <= flow && flow <= capacity);
+        balance[from] -= flow;
+        balance[to] += flow;
+
+        let reduced = cost + result.potentials[from] - result.potentials[to];
+        if flow < capacity {
+            assert!(reduced >= 0);
+        }
+        if flow > 0 {
+            assert!(reduced <= 0);
+        }
+    }
+    assert_eq!(balance, [0; 5]);
+
+    let mut graph = MinCostFlow::new(3);
+    graph.add_edge(0, 1, 2, 1);
+    graph.add_edge(1, 2, 5, 1);
+    graph.add_supply(0, 3);
+    graph.add_demand(2, 3);
+    assert_eq!(graph.solve(), None);
+    assert!(graph.edges().iter().all(|e| e.flow == 0));
+    graph.add_supply(0, -1);
+    graph.add_demand(2, -2);
+    graph.add_demand(1, 1);
+    assert_eq!(graph.solve().map(|r| r.cost), Some(3));
+    assert_eq!(graph.solve().map(|r| r.flows), Some(vec![2, 1]));
+
+    let mut graph = MinCostFlow::new(2);
+    graph.add_edge(0, 1, 5, 1);
+    graph.add_supply(0, 3);
+    graph.add_demand(1, 2);
+    assert_eq!(graph.solve(), None);
+}
+
+#[test]
+fn test_mincostflow_to_dot() {
+    let mut graph = MinCostFlow::new(3);
+    graph.add_edge(0, 1, 2, 1);
+    graph.add_edge(1, 2, 3, 2);
+    graph.add_edge(0, 2, 4, 5);
+    assert_eq!(graph.min_cost_max_flow_limited(0, 2, 3), (3, 11));
+
+    assert_eq!(
+        graph.to_dot(0),
+        "digraph {\n    \
+         0 [style=filled, fillcolor=lightblue];\n    \
+         1 [style=filled, fillcolor=lightblue];\n    \
+         2 [style=filled, fillcolor=lightblue];\n    \
+         0 -> 1 [label=\"2/2, 1\"];\n    \
+         1 -> 2 [label=\"2/3, 2\"];\n    \
+         0 -> 2 [label=\"1/4, 5\"];\n}\n"
+    );
+
+    assert_eq!(graph.min_cost_max_flow(0, 2), (3, 15));
+    assert_eq!(
+        graph.to_dot(0),
+        "digraph {\n    \
+         0 [style=filled, fillcolor=lightblue];\n    \
+         1;\n    2;\n    \
+         0 -> 1 [label=\"2/2, 1\"];\n    \
+         1 -> 2 [label=\"2/3, 2\"];\n    \
+         0 -> 2 [label=\"4/4, 5\"];\n}\n"
+    );
 }
@@ -0,0 +1,190 @@
+use crate::maxflow::{EdgeInternal, MaxFlow};
+use cargo_snippet::snippet;
+
+#[snippet(name = "push_relabel", include = "maxflow")]
+impl MaxFlow {
+    fn push(&mut self, node: usize, i: usize, amount: isize, excess: &mut [isize]) {
+        let EdgeInternal { to, rev_ref, .. } = self.edges[node][i];
+        self.edges[node][i].capacity -= amount;
+        self.edges[to][rev_ref].capacity += amount;
+        excess[node] -= amount;
+        excess[to] += amount;
+    }
+
+    fn initial_heights(&self, sink: usize) -> Vec<usize> {
+        let mut heights = vec![self.nodes + 1; self.nodes];
+        let mut queue = std::collections::VecDeque::new();
+        heights[sink] = 0;
+        queue.push_back(sink);
+
+        while let Some(node) = queue.pop_front() {
+            for edge in &self.edges[node] {
+                let reachable = self.edges[edge.to][edge.rev_ref].capacity > 0;
+                if reachable && heights[edge.to] == self.nodes + 1 {
+                    heights[edge.to] = heights[node] + 1;
+                    queue.push_back(edge.to);
+                }
+            }
+        }
+
+        heights
+    }
+
+    pub(crate) fn max_flow_push_relabel(&mut self, source: usize, sink: usize) -> isize {
+        let n = self.nodes;
+        if source == sink {
+            return 0;
+        }
+
+        let mut heights = self.initial_heights(sink);
+        heights[source] = n;
+        let mut counts = vec![0; n * 2 + 2];
+        for &height in &heights {
+            counts[height] += 1;
+        }
+
+        let mut excess = vec![0; n];
+        let mut buckets = vec![Vec::new(); n * 2 + 2];
+        let mut highest = 0;
+        for i in 0..self.edges[source].len() {
+            let EdgeInternal { to, capacity, .. } = self.edges[source][i];
+            if capacity > 0 {
+                if excess[to] == 0 && to != sink && to != source {
+                    buckets[heights[to]].push(to);
+                    highest = highest.max(heights[to]);
+                }
+                self.push(source, i, capacity, &mut excess);
+            }
+        }
+
+        let mut current = vec![0; n];
+        loop {
+            while highest > 0 && buckets[highest].is_empty() {
+                highest -= 1;
+            }
+            let node = match buckets[highest].pop() {
+                Some(node) => node,
+                None => break,
+            };
+            if heights[node] != highest || excess[node] == 0 {
+                continue;
+            }
+
+            while excess[node] > 0 {
+                if current[node] == self.edges[node].len() {
+                    let old = heights[node];
+                    let new = self.edges[node]
+                        .iter()
+                        .filter(|edge| edge.capacity > 0)
+                        .map(|edge| heights[edge.to] + 1)
+                        .min()
+                        .unwrap();
+                    counts[old] -= 1;
+
+                    if old < n && counts[old] == 0 {
+                        for other in 0..n {
+                            if old < heights[other] && heights[other] < n {
+                                counts[heights[other]] -= 1;
+                                heights[other] = n + 1;
+                                counts[n + 1] += 1;
+                                if excess[other] > 0 {
+                                    buckets[n + 1].push(other);
+                                    highest = highest.max(n + 1);
+                                }
+                            }
+                        }
+                        heights[node] = new.max(n + 1);
+                    } else {
+                        heights[node] = new;
+                    }
+
+                    counts[heights[node]] += 1;
+                    highest = highest.max(heights[node]);
+                    current[node] = 0;
+                    continue;
+                }
+
+                let i = current[node];
+                let EdgeInternal { to, capacity, .. } = self.edges[node][i];
+                if capacity > 0 && heights[node] == heights[to] + 1 {
+                    if excess[to] == 0 && to != sink && to != source {
+                        buckets[heights[to]].push(to);
+                        highest = highest.max(heights[to]);
+                    }
+                    let amount = excess[node].min(capacity);
+                    self.push(node, i, amount, &mut excess);
+                } else {
+                    current[node] += 1;
+                }
+            }
+        }
+
+        excess[sink]
+    }
+}
+
+#[test]
+fn test_push_relabel() {
+    use crate::random::RNG;
+
+    let edges = [
+        (0, 1, 9),
+        (0, 2, 3),
+        (1, 2, 3),
+        (1, 3, 4),
+        (2, 3, 5),
+        (2, 4, 4),
+        (3, 4, 8),
+    ];
+    let mut graph = MaxFlow::new(5);
+    for &(from, to, capacity) in &edges {
+        graph.add_edge(from, to, capacity);
+    }
+    assert_eq!(graph.max_flow_push_relabel(0, 4), 10);
+
+    let mut rng = RNG(13579);
+    for _ in 0..300 {
+        let nodes = rng.rand() as usize % 8 + 2;
+        let edge_count = rng.rand() as usize % 20;
+        let mut graph = MaxFlow::new(nodes);
+        let mut reference = MaxFlow::new(nodes);
+        for _ in 0..edge_count {
+            let from = rng.rand() as usize % nodes;
+            let to = rng.rand() as usize % nodes;
+            let capacity = (rng.rand() % 10) as isize;
+            graph.add_edge(from, to, capacity);
+            reference.add_edge(from, to, capacity);
+        }
+        let (source, sink) = (0, nodes - 1);
+
+        let flow = graph.max_flow_push_relabel(source, sink);
+        assert_eq!(flow, reference.max_flow(source, sink));
+
+        let mut balance = vec![0; nodes];
+        for edge in graph.edges() {
+            assert!(0 <= edge.flow && edge.flow <= edge.capacity);
+            balance[edge.from] -= edge.flow;
+            balance[edge.to] += edge.flow;
+        }
+        for (node, &b) in balance.iter().enumerate() {
+            let expected = if node == source {
+                -flow
+            } else if node == sink {
+                flow
+            } else {
+                0
+            };
+            assert_eq!(b, expected);
+        }
+
+        let min_cut = graph.min_cut(source);
+        assert!(!min_cut[sink]);
+        let cut: isize = graph
+            .edges()
+            .iter()
+            .filter(|e| min_cut[e.from] && !min_cut[e.to])
+            .map(|e| e.capacity)
+            .sum();
+        assert_eq!(cut, flow);
+    }
+}
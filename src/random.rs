@@ -1,11 +1,11 @@
 use cargo_snippet::snippet;
 
 #[snippet("rng")]
-struct RNG(u64);
+pub(crate) struct RNG(pub(crate) u64);
 
 #[snippet("rng")]
 impl RNG {
-    fn rand(&mut self) -> u64 {
+    pub(crate) fn rand(&mut self) -> u64 {
         let RNG(x) = self;
         *x ^= *x << 13;
         *x ^= *x >> 7;
@@ -0,0 +1,133 @@
+use crate::graph::{Graph, GraphEdge};
+use crate::segtree::Monoid;
+use cargo_snippet::snippet;
+
+#[snippet(name = "rerooting", include = "graph, segtree")]
+pub fn rerooting<M, E, V>(graph: &Graph, add_edge: E, add_root: V) -> Vec<M::S>
+where
+    M: Monoid,
+    E: Fn(M::S, &GraphEdge) -> M::S,
+    V: Fn(M::S, usize) -> M::S,
+{
+    let nodes = graph.nodes;
+    if nodes == 0 {
+        return Vec::new();
+    }
+
+    let mut parent = vec![None; nodes];
+    let mut order = Vec::with_capacity(nodes);
+    let mut stack = vec![0];
+    while let Some(node) = stack.pop() {
+        order.push(node);
+        for edge in &graph.edges[node] {
+            if Some(edge.node_to) != parent[node] {
+                parent[edge.node_to] = Some(node);
+                stack.push(edge.node_to);
+            }
+        }
+    }
+
+    // `add_edge` receives the value of the subtree behind `edge.node_to`, seen through `edge`.
+    let mut down = vec![M::ID; nodes];
+    for &node in order.iter().rev() {
+        let product = graph.edges[node]
+            .iter()
+            .filter(|edge| Some(edge.node_to) != parent[node])
+            .fold(M::ID, |acc, edge| {
+                M::product(acc, add_edge(down[edge.node_to].clone(), edge))
+            });
+        down[node] = add_root(product, node);
+    }
+
+    let mut up = vec![M::ID; nodes];
+    let mut answers = vec![M::ID; nodes];
+    for &node in &order {
+        let items: Vec<M::S> = graph.edges[node]
+            .iter()
+            .map(|edge| {
+                if Some(edge.node_to) == parent[node] {
+                    add_edge(up[node].clone(), edge)
+                } else {
+                    add_edge(down[edge.node_to].clone(), edge)
+                }
+            })
+            .collect();
+
+        let mut suffix = vec![M::ID; items.len() + 1];
+        for i in (0..items.len()).rev() {
+            suffix[i] = M::product(items[i].clone(), suffix[i + 1].clone());
+        }
+
+        let mut prefix = M::ID;
+        for (i, edge) in graph.edges[node].iter().enumerate() {
+            if Some(edge.node_to) != parent[node] {
+                let excluded = M::product(prefix.clone(), suffix[i + 1].clone());
+                up[edge.node_to] = add_root(excluded, node);
+            }
+            prefix = M::product(prefix, items[i].clone());
+        }
+        answers[node] = add_root(prefix, node);
+    }
+
+    answers
+}
+
+#[test]
+fn test_rerooting() {
+    struct DistanceSum;
+
+    impl Monoid for DistanceSum {
+        type S = (isize, isize);
+        const ID: Self::S = (0, 0);
+
+        fn product(lhs: Self::S, rhs: Self::S) -> Self::S {
+            (lhs.0 + rhs.0, lhs.1 + rhs.1)
+        }
+    }
+
+    struct Height;
+
+    impl Monoid for Height {
+        type S = isize;
+        const ID: Self::S = 0;
+
+        fn product(lhs: Self::S, rhs: Self::S) -> Self::S {
+            lhs.max(rhs)
+        }
+    }
+
+    let mut graph = Graph::new(9);
+    graph.edge_undirected_costed(0, 1, 3);
+    graph.edge_undirected_costed(1, 2, 4);
+    graph.edge_undirected_costed(1, 3, 1);
+    graph.edge_undirected_costed(3, 4, 5);
+    graph.edge_undirected_costed(0, 5, 9);
+    graph.edge_undirected_costed(5, 6, 2);
+    graph.edge_undirected_costed(5, 7, 6);
+    graph.edge_undirected_costed(7, 8, 3);
+
+    let sums = rerooting::<DistanceSum, _, _>(
+        &graph,
+        |(sum, count), edge| (sum + count * edge.cost, count),
+        |(sum, count), _| (sum, count + 1),
+    );
+    let heights = rerooting::<Height, _, _>(
+        &graph,
+        |height, edge| height + edge.cost,
+        |height, _| height,
+    );
+
+    for node in 0..9 {
+        let dists: Vec<isize> = graph.dijkstra(node).into_iter().flatten().collect();
+        assert_eq!(sums[node], (dists.iter().sum(), 9));
+        assert_eq!(heights[node], *dists.iter().max().unwrap());
+    }
+
+    let single =
+        rerooting::<Height, _, _>(&Graph::new(1), |height, _| height + 1, |height, _| height);
+    assert_eq!(single, vec![0]);
+
+    let empty =
+        rerooting::<Height, _, _>(&Graph::new(0), |height, _| height + 1, |height, _| height);
+    assert!(empty.is_empty());
+}
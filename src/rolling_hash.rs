@@ -1,7 +1,7 @@
 use cargo_snippet::snippet;
 
 #[snippet("rollhash")]
-struct RNG(u64);
+pub(crate) struct RNG(pub(crate) u64);
 
 #[snippet("rollhash")]
 impl Iterator for RNG {
@@ -18,13 +18,13 @@ impl Iterator for RNG {
 }
 
 #[snippet("rollhash")]
-const RHMOD: u64 = (1 << 61) - 1;
+pub(crate) const RHMOD: u64 = (1 << 61) - 1;
 
 #[snippet("rollhash")]
 type Hash = (u64, u64, u64, u64, u64, u64, u64, u64);
 
 #[snippet("rollhash")]
-fn u64_madd_mod(a: u64, b: u64, c: u64) -> u64 {
+pub(crate) fn u64_madd_mod(a: u64, b: u64, c: u64) -> u64 {
     ((a as u128 + b as u128 * c as u128) % RHMOD as u128) as u64
 }
 
@@ -3,7 +3,7 @@ use cargo_snippet::snippet;
 use std::marker::PhantomData;
 
 #[snippet("lazy_segtree")]
-trait Morphism<S: Clone> {
+pub(crate) trait Morphism<S: Clone> {
     type F: Clone;
     const ID: Self::F;
 
@@ -90,7 +90,7 @@ impl Morphism<ValueWithWidth> for AddMorphismWithWidth {
 }
 
 #[snippet("lazy_segtree")]
-struct LazySegTree<Mono: Monoid, Morph: Morphism<Mono::S>> {
+pub(crate) struct LazySegTree<Mono: Monoid, Morph: Morphism<Mono::S>> {
     orig_len: usize,
     padded_len: usize,
     tree: Vec<Mono::S>,
@@ -127,7 +127,7 @@ impl<Mono: Monoid, Morph: Morphism<Mono::S>> From<Vec<Mono::S>> for LazySegTree<
 
 #[snippet("lazy_segtree")]
 impl<Mono: Monoid, Morph: Morphism<Mono::S>> LazySegTree<Mono, Morph> {
-    fn new(len: usize) -> Self {
+    pub(crate) fn new(len: usize) -> Self {
         vec![Mono::ID; len].into()
     }
 
@@ -177,14 +177,14 @@ impl<Mono: Monoid, Morph: Morphism<Mono::S>> LazySegTree<Mono, Morph> {
         self.eval(index);
     }
 
-    fn set(&mut self, index: usize, value: Mono::S) {
+    pub(crate) fn set(&mut self, index: usize, value: Mono::S) {
         assert!(index < self.orig_len);
         self.recursive_eval(self.padded_len + index);
         self.tree[self.padded_len + index] = value;
         self.update(self.padded_len + index);
     }
 
-    fn get(&mut self, index: usize) -> &Mono::S {
+    pub(crate) fn get(&mut self, index: usize) -> &Mono::S {
         assert!(index < self.orig_len);
         self.recursive_eval(self.padded_len + index);
         &self.tree[self.padded_len + index]
@@ -212,7 +212,7 @@ impl<Mono: Monoid, Morph: Morphism<Mono::S>> LazySegTree<Mono, Morph> {
         }
     }
 
-    fn product(&mut self, l: usize, r: usize) -> Mono::S {
+    pub(crate) fn product(&mut self, l: usize, r: usize) -> Mono::S {
         self.product_with_segment_range(l, r, 0, self.padded_len, 1)
     }
 
@@ -238,11 +238,11 @@ impl<Mono: Monoid, Morph: Morphism<Mono::S>> LazySegTree<Mono, Morph> {
         }
     }
 
-    fn apply(&mut self, l: usize, r: usize, f: Morph::F) {
+    pub(crate) fn apply(&mut self, l: usize, r: usize, f: Morph::F) {
         self.apply_with_segment_range(l, r, &f, 0, self.padded_len, 1);
     }
 
-    fn product_all(&mut self) -> Mono::S {
+    pub(crate) fn product_all(&mut self) -> Mono::S {
         self.eval(1);
         self.tree[1].clone()
     }
@@ -1,6 +1,9 @@
 mod lazy_segtree;
 mod segtree;
 
+pub(crate) use self::lazy_segtree::{LazySegTree, Morphism};
+pub(crate) use self::segtree::SegTree;
+
 use cargo_snippet::snippet;
 
 #[snippet("segtree")]
@@ -9,7 +12,7 @@ use std::marker::PhantomData;
 
 #[snippet("segtree")]
 #[snippet("lazy_segtree")]
-trait Identity: Copy {
+pub(crate) trait Identity: Copy {
     const ZERO: Self;
     const ONE: Self;
 }
@@ -33,7 +36,7 @@ impl_identity!(usize, u8, u16, u32, u64, u128, isize, i8, i16, i32, i64, i128);
 
 #[snippet("segtree")]
 #[snippet("lazy_segtree")]
-trait Monoid {
+pub(crate) trait Monoid {
     type S: Clone;
     const ID: Self::S;
 
@@ -42,7 +45,7 @@ trait Monoid {
 
 #[snippet("segtree")]
 #[snippet("lazy_segtree")]
-struct AddMonoid<T>(PhantomData<T>);
+pub(crate) struct AddMonoid<T>(PhantomData<T>);
 
 #[snippet("segtree")]
 #[snippet("lazy_segtree")]
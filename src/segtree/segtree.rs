@@ -2,7 +2,7 @@ use super::{ceil_log2, Monoid};
 use cargo_snippet::snippet;
 
 #[snippet("segtree")]
-struct SegTree<T: Monoid> {
+pub(crate) struct SegTree<T: Monoid> {
     orig_len: usize,
     padded_len: usize,
     tree: Vec<T::S>,
@@ -35,7 +35,7 @@ impl<M: Monoid> From<Vec<M::S>> for SegTree<M> {
 
 #[snippet("segtree")]
 impl<M: Monoid> SegTree<M> {
-    fn new(len: usize) -> Self {
+    pub(crate) fn new(len: usize) -> Self {
         vec![M::ID; len].into()
     }
 
@@ -59,13 +59,13 @@ impl<M: Monoid> SegTree<M> {
         self.update(index / 2);
     }
 
-    fn set(&mut self, index: usize, value: M::S) {
+    pub(crate) fn set(&mut self, index: usize, value: M::S) {
         assert!(index < self.orig_len);
         self.tree[self.padded_len + index] = value;
         self.update(self.padded_len + index);
     }
 
-    fn get(&self, index: usize) -> &M::S {
+    pub(crate) fn get(&self, index: usize) -> &M::S {
         assert!(index < self.orig_len);
         &self.tree[self.padded_len + index]
     }
@@ -90,11 +90,11 @@ impl<M: Monoid> SegTree<M> {
         }
     }
 
-    fn product(&self, l: usize, r: usize) -> M::S {
+    pub(crate) fn product(&self, l: usize, r: usize) -> M::S {
         self.product_with_segment_range(l, r, 0, self.padded_len, 1)
     }
 
-    fn product_all(&self) -> M::S {
+    pub(crate) fn product_all(&self) -> M::S {
         self.tree[1].clone()
     }
 }
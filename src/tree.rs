@@ -0,0 +1,456 @@
+use crate::graph::Graph;
+use crate::rolling_hash::{u64_madd_mod, RHMOD, RNG};
+use cargo_snippet::snippet;
+
+#[snippet(name = "tree", include = "graph")]
+pub struct RootedTree {
+    pub root: usize,
+    pub parent: Vec<Option<usize>>,
+    pub depth: Vec<usize>,
+    pub dist: Vec<isize>,
+    pub order: Vec<usize>,
+    pub index: Vec<usize>,
+    pub size: Vec<usize>,
+    ancestors: Vec<Vec<usize>>,
+}
+
+#[snippet("tree")]
+impl RootedTree {
+    pub fn new(graph: &Graph, root: usize) -> Self {
+        let nodes = graph.nodes;
+        let mut parent = vec![None; nodes];
+        let mut depth = vec![0; nodes];
+        let mut dist = vec![0; nodes];
+        let mut order = Vec::with_capacity(nodes);
+        let mut visited = vec![false; nodes];
+        let mut stack = vec![root];
+        visited[root] = true;
+
+        while let Some(node) = stack.pop() {
+            order.push(node);
+            for edge in graph.edges[node].iter().rev() {
+                if visited[edge.node_to] {
+                    continue;
+                }
+                visited[edge.node_to] = true;
+                parent[edge.node_to] = Some(node);
+                depth[edge.node_to] = depth[node] + 1;
+                dist[edge.node_to] = dist[node] + edge.cost;
+                stack.push(edge.node_to);
+            }
+        }
+
+        let mut index = vec![0; nodes];
+        for (i, &node) in order.iter().enumerate() {
+            index[node] = i;
+        }
+
+        let mut size = vec![1; nodes];
+        for &node in order.iter().rev() {
+            if let Some(p) = parent[node] {
+                size[p] += size[node];
+            }
+        }
+
+        let mut ancestors = vec![(0..nodes)
+            .map(|node| parent[node].unwrap_or(node))
+            .collect::<Vec<_>>()];
+        while 1 << ancestors.len() < nodes {
+            let last = ancestors.last().unwrap();
+            let next = (0..nodes).map(|node| last[last[node]]).collect();
+            ancestors.push(next);
+        }
+
+        Self {
+            root,
+            parent,
+            depth,
+            dist,
+            order,
+            index,
+            size,
+            ancestors,
+        }
+    }
+
+    pub fn kth_ancestor(&self, mut node: usize, k: usize) -> Option<usize> {
+        if k > self.depth[node] {
+            return None;
+        }
+        for (i, ancestors) in self.ancestors.iter().enumerate() {
+            if k >> i & 1 == 1 {
+                node = ancestors[node];
+            }
+        }
+        Some(node)
+    }
+
+    pub fn lca(&self, a: usize, b: usize) -> usize {
+        let (a, mut b) = if self.depth[a] < self.depth[b] {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        b = self.kth_ancestor(b, self.depth[b] - self.depth[a]).unwrap();
+        if a == b {
+            return a;
+        }
+
+        let mut a = a;
+        for ancestors in self.ancestors.iter().rev() {
+            if ancestors[a] != ancestors[b] {
+                a = ancestors[a];
+                b = ancestors[b];
+            }
+        }
+        self.ancestors[0][a]
+    }
+
+    pub fn dist(&self, a: usize, b: usize) -> isize {
+        self.dist[a] + self.dist[b] - 2 * self.dist[self.lca(a, b)]
+    }
+}
+
+#[snippet(name = "virtual_tree", include = "tree")]
+impl RootedTree {
+    pub fn virtual_tree(&self, vertices: &[usize]) -> (Graph, Vec<usize>) {
+        let mut nodes = vertices.to_vec();
+        nodes.sort_unstable_by_key(|&node| self.index[node]);
+        nodes.dedup();
+        for i in 1..nodes.len() {
+            let lca = self.lca(nodes[i - 1], nodes[i]);
+            nodes.push(lca);
+        }
+        nodes.sort_unstable_by_key(|&node| self.index[node]);
+        nodes.dedup();
+
+        let mut graph = Graph::new(nodes.len());
+        for i in 1..nodes.len() {
+            let parent = self.lca(nodes[i - 1], nodes[i]);
+            let parent_id = nodes
+                .binary_search_by_key(&self.index[parent], |&node| self.index[node])
+                .unwrap();
+            let cost = self.dist[nodes[i]] - self.dist[parent];
+            graph.edge_undirected_costed(parent_id, i, cost);
+        }
+        (graph, nodes)
+    }
+}
+
+#[snippet(name = "euler_tour", include = "graph")]
+pub struct EulerTour {
+    pub tour: Vec<usize>,
+    pub first: Vec<usize>,
+    pub depth: Vec<usize>,
+    sparse: Vec<Vec<usize>>,
+}
+
+#[snippet("euler_tour")]
+impl EulerTour {
+    pub fn new(graph: &Graph, root: usize) -> Self {
+        let nodes = graph.nodes;
+        let mut tour = Vec::with_capacity(nodes * 2);
+        let mut first = vec![usize::MAX; nodes];
+        let mut depth = vec![0; nodes];
+        let mut calls = vec![(root, 0)];
+        first[root] = 0;
+        tour.push(root);
+
+        while let Some(&(node, i)) = calls.last() {
+            if let Some(edge) = graph.edges[node].get(i) {
+                calls.last_mut().unwrap().1 += 1;
+                if first[edge.node_to] == usize::MAX {
+                    first[edge.node_to] = tour.len();
+                    depth[edge.node_to] = depth[node] + 1;
+                    tour.push(edge.node_to);
+                    calls.push((edge.node_to, 0));
+                }
+                continue;
+            }
+
+            calls.pop();
+            if let Some(&(parent, _)) = calls.last() {
+                tour.push(parent);
+            }
+        }
+
+        let mut sparse = vec![tour.clone()];
+        while 2 << (sparse.len() - 1) <= tour.len() {
+            let width = 1 << (sparse.len() - 1);
+            let last = sparse.last().unwrap();
+            let next = (0..=tour.len() - width * 2)
+                .map(|i| {
+                    let (a, b) = (last[i], last[i + width]);
+                    if depth[a] <= depth[b] {
+                        a
+                    } else {
+                        b
+                    }
+                })
+                .collect();
+            sparse.push(next);
+        }
+
+        Self {
+            tour,
+            first,
+            depth,
+            sparse,
+        }
+    }
+
+    pub fn lca(&self, a: usize, b: usize) -> usize {
+        let l = self.first[a].min(self.first[b]);
+        let r = self.first[a].max(self.first[b]) + 1;
+        let level = (usize::BITS - 1 - (r - l).leading_zeros()) as usize;
+        let x = self.sparse[level][l];
+        let y = self.sparse[level][r - (1 << level)];
+        if self.depth[x] <= self.depth[y] {
+            x
+        } else {
+            y
+        }
+    }
+}
+
+#[snippet(name = "tree_diameter", include = "graph")]
+fn farthest(graph: &Graph, root: usize) -> (usize, Vec<isize>, Vec<Option<usize>>) {
+    let mut dist = vec![0; graph.nodes];
+    let mut parent = vec![None; graph.nodes];
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        for edge in &graph.edges[node] {
+            if Some(edge.node_to) != parent[node] && edge.node_to != root {
+                parent[edge.node_to] = Some(node);
+                dist[edge.node_to] = dist[node] + edge.cost;
+                stack.push(edge.node_to);
+            }
+        }
+    }
+    let far = (0..graph.nodes).max_by_key(|&node| dist[node]).unwrap();
+    (far, dist, parent)
+}
+
+#[snippet("tree_diameter")]
+pub fn tree_diameter(graph: &Graph) -> (isize, Vec<usize>) {
+    if graph.nodes == 0 {
+        return (0, Vec::new());
+    }
+
+    let (a, _, _) = farthest(graph, 0);
+    let (b, dist, parent) = farthest(graph, a);
+    let mut path = vec![b];
+    while let Some(p) = parent[*path.last().unwrap()] {
+        path.push(p);
+    }
+    (dist[b], path)
+}
+
+#[snippet(name = "tree_hash", include = "graph, rollhash")]
+pub struct TreeHasher {
+    bases: Vec<u64>,
+}
+
+#[snippet("tree_hash")]
+impl TreeHasher {
+    pub fn new(nodes: usize) -> Self {
+        use std::time::{SystemTime, UNIX_EPOCH};
+        let now = SystemTime::now();
+        let time = now.duration_since(UNIX_EPOCH).unwrap().as_millis() as u64;
+        let rng = RNG(time);
+        let bases = rng.take(nodes + 1).map(|x| x % (RHMOD - 3) + 2).collect();
+
+        TreeHasher { bases }
+    }
+
+    pub fn hash_rooted(&self, graph: &Graph, root: usize) -> Vec<u64> {
+        let mut parent = vec![None; graph.nodes];
+        let mut order = Vec::with_capacity(graph.nodes);
+        let mut stack = vec![root];
+        while let Some(node) = stack.pop() {
+            order.push(node);
+            for edge in &graph.edges[node] {
+                if Some(edge.node_to) != parent[node] && edge.node_to != root {
+                    parent[edge.node_to] = Some(node);
+                    stack.push(edge.node_to);
+                }
+            }
+        }
+
+        let mut height = vec![0; graph.nodes];
+        for &node in order.iter().rev() {
+            if let Some(p) = parent[node] {
+                height[p] = height[p].max(height[node] + 1);
+            }
+        }
+
+        let mut hash = vec![1; graph.nodes];
+        for &node in order.iter().rev() {
+            if let Some(p) = parent[node] {
+                let factor = (self.bases[height[p]] + hash[node]) % RHMOD;
+                hash[p] = u64_madd_mod(0, hash[p], factor);
+            }
+        }
+        hash
+    }
+
+    pub fn hash_unrooted(&self, graph: &Graph) -> u64 {
+        let nodes = graph.nodes;
+        if nodes == 0 {
+            return 0;
+        }
+
+        let mut parent = vec![None; nodes];
+        let mut order = Vec::with_capacity(nodes);
+        let mut stack = vec![0];
+        while let Some(node) = stack.pop() {
+            order.push(node);
+            for edge in &graph.edges[node] {
+                if Some(edge.node_to) != parent[node] && edge.node_to != 0 {
+                    parent[edge.node_to] = Some(node);
+                    stack.push(edge.node_to);
+                }
+            }
+        }
+
+        let mut size = vec![1; nodes];
+        let mut largest = vec![0; nodes];
+        for &node in order.iter().rev() {
+            largest[node] = largest[node].max(nodes - size[node]);
+            if let Some(p) = parent[node] {
+                size[p] += size[node];
+                largest[p] = largest[p].max(size[node]);
+            }
+        }
+
+        (0..nodes)
+            .filter(|&node| largest[node] * 2 <= nodes)
+            .map(|centroid| self.hash_rooted(graph, centroid)[centroid])
+            .min()
+            .unwrap()
+    }
+}
+
+#[cfg(test)]
+fn sample_tree() -> Graph {
+    let mut graph = Graph::new(10);
+    graph.edge_undirected_costed(0, 1, 3);
+    graph.edge_undirected_costed(0, 2, 1);
+    graph.edge_undirected_costed(1, 3, 4);
+    graph.edge_undirected_costed(1, 4, 1);
+    graph.edge_undirected_costed(2, 5, 5);
+    graph.edge_undirected_costed(4, 6, 9);
+    graph.edge_undirected_costed(4, 7, 2);
+    graph.edge_undirected_costed(5, 8, 6);
+    graph.edge_undirected_costed(8, 9, 5);
+    graph
+}
+
+#[test]
+fn test_rooted_tree() {
+    let graph = sample_tree();
+    let tree = RootedTree::new(&graph, 0);
+
+    assert_eq!(tree.parent[0], None);
+    assert_eq!(tree.parent[6], Some(4));
+    assert_eq!(tree.depth[9], 4);
+    assert_eq!(tree.dist[9], 17);
+    assert_eq!(tree.size[1], 5);
+    assert_eq!(tree.order, vec![0, 1, 3, 4, 6, 7, 2, 5, 8, 9]);
+    assert_eq!(tree.kth_ancestor(9, 0), Some(9));
+    assert_eq!(tree.kth_ancestor(9, 3), Some(2));
+    assert_eq!(tree.kth_ancestor(9, 4), Some(0));
+    assert_eq!(tree.kth_ancestor(9, 5), None);
+    assert_eq!(tree.lca(6, 7), 4);
+    assert_eq!(tree.lca(3, 7), 1);
+    assert_eq!(tree.lca(6, 9), 0);
+    assert_eq!(tree.lca(4, 6), 4);
+    assert_eq!(tree.lca(5, 5), 5);
+    assert_eq!(tree.dist(6, 9), 30);
+    assert_eq!(tree.dist(3, 7), 7);
+
+    let euler_tour = EulerTour::new(&graph, 0);
+    assert_eq!(euler_tour.tour.len(), 19);
+    for a in 0..10 {
+        for b in 0..10 {
+            assert_eq!(euler_tour.lca(a, b), tree.lca(a, b));
+        }
+    }
+
+    let mut path = Graph::new(200000);
+    for node in 1..200000 {
+        path.edge_undirected(node - 1, node);
+    }
+    let tree = RootedTree::new(&path, 0);
+    assert_eq!(tree.lca(199999, 100000), 100000);
+    assert_eq!(tree.dist(199999, 3), 199996);
+    let euler_tour = EulerTour::new(&path, 0);
+    assert_eq!(euler_tour.lca(199999, 100000), 100000);
+}
+
+#[test]
+fn test_tree_diameter() {
+    let graph = sample_tree();
+    let (diameter, path) = tree_diameter(&graph);
+    assert_eq!(diameter, 30);
+    assert!(path == vec![6, 4, 1, 0, 2, 5, 8, 9] || path == vec![9, 8, 5, 2, 0, 1, 4, 6]);
+
+    let (diameter, path) = tree_diameter(&Graph::new(1));
+    assert_eq!((diameter, path), (0, vec![0]));
+
+    let (diameter, path) = tree_diameter(&Graph::new(0));
+    assert_eq!((diameter, path), (0, vec![]));
+}
+
+#[test]
+fn test_tree_hash() {
+    let build = |edges: &[(usize, usize)]| {
+        let mut graph = Graph::new(edges.len() + 1);
+        for &(a, b) in edges {
+            graph.edge_undirected(a, b);
+        }
+        graph
+    };
+    let a = build(&[(0, 1), (1, 2), (1, 3), (3, 4), (3, 5), (0, 6)]);
+    let b = build(&[(4, 2), (2, 0), (0, 6), (2, 3), (3, 1), (3, 5)]);
+    let c = build(&[(0, 1), (1, 2), (1, 3), (3, 4), (4, 5), (0, 6)]);
+    let hasher = TreeHasher::new(7);
+
+    assert_eq!(hasher.hash_unrooted(&a), hasher.hash_unrooted(&b));
+    assert_ne!(hasher.hash_unrooted(&a), hasher.hash_unrooted(&c));
+    assert_eq!(hasher.hash_unrooted(&Graph::new(0)), 0);
+
+    let hash_a = hasher.hash_rooted(&a, 0);
+    let hash_b = hasher.hash_rooted(&b, 0);
+    assert_eq!(hash_a[0], hash_b[0]);
+    assert_eq!(hash_a[1], hash_b[2]);
+    assert_eq!(hash_a[3], hash_b[3]);
+    assert_eq!(hash_a[4], hash_a[5]);
+    assert_eq!(hash_a[4], hash_a[6]);
+    assert_ne!(hash_a[1], hash_a[3]);
+    assert_ne!(hash_a[0], hasher.hash_rooted(&a, 1)[1]);
+}
+
+#[test]
+fn test_virtual_tree() {
+    let graph = sample_tree();
+    let tree = RootedTree::new(&graph, 0);
+
+    let (virtual_tree, mapping) = tree.virtual_tree(&[9, 6, 3, 7, 6]);
+    assert_eq!(mapping, vec![0, 1, 3, 4, 6, 7, 9]);
+    assert_eq!(virtual_tree.nodes, 7);
+    assert_eq!(virtual_tree.edge_count, 6);
+    for (i, &a) in mapping.iter().enumerate() {
+        let dists = virtual_tree.dijkstra(i);
+        for (j, &b) in mapping.iter().enumerate() {
+            assert_eq!(dists[j], Some(tree.dist(a, b)));
+        }
+    }
+
+    let (virtual_tree, mapping) = tree.virtual_tree(&[8, 9]);
+    assert_eq!(mapping, vec![8, 9]);
+    assert_eq!(virtual_tree.dijkstra(0), vec![Some(0), Some(5)]);
+
+    let (virtual_tree, mapping) = tree.virtual_tree(&[]);
+    assert_eq!((virtual_tree.nodes, mapping), (0, vec![]));
+}
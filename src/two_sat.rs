@@ -0,0 +1,63 @@
+use crate::graph::Graph;
+use cargo_snippet::snippet;
+
+#[snippet(name = "two_sat", include = "graph_scc")]
+pub struct TwoSat {
+    variables: usize,
+    graph: Graph,
+}
+
+#[snippet("two_sat")]
+impl TwoSat {
+    pub fn new(variables: usize) -> Self {
+        Self {
+            variables,
+            graph: Graph::new(variables * 2),
+        }
+    }
+
+    pub fn add_clause(&mut self, i: usize, f: bool, j: usize, g: bool) {
+        assert!(i < self.variables);
+        assert!(j < self.variables);
+        self.graph
+            .edge_directed(2 * i + (!f) as usize, 2 * j + g as usize);
+        self.graph
+            .edge_directed(2 * j + (!g) as usize, 2 * i + f as usize);
+    }
+
+    pub fn satisfiable(&self) -> Option<Vec<bool>> {
+        let (_, ids) = self.graph.scc_ids();
+        (0..self.variables)
+            .map(|i| {
+                if ids[2 * i] == ids[2 * i + 1] {
+                    None
+                } else {
+                    Some(ids[2 * i] < ids[2 * i + 1])
+                }
+            })
+            .collect()
+    }
+}
+
+#[test]
+fn test_two_sat() {
+    let clauses = [
+        (0, true, 1, true),
+        (0, false, 2, false),
+        (1, false, 2, true),
+        (2, true, 3, false),
+        (3, true, 3, true),
+    ];
+    let mut two_sat = TwoSat::new(4);
+    for &(i, f, j, g) in &clauses {
+        two_sat.add_clause(i, f, j, g);
+    }
+    let answer = two_sat.satisfiable().unwrap();
+    for &(i, f, j, g) in &clauses {
+        assert!(answer[i] == f || answer[j] == g);
+    }
+    assert_eq!(answer, vec![false, true, true, true]);
+
+    two_sat.add_clause(1, false, 1, false);
+    assert_eq!(two_sat.satisfiable(), None);
+}
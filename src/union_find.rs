@@ -50,3 +50,325 @@ fn test_union_find() {
     assert!(!uf.same_root(1, 4));
     assert!(uf.same_root(1, 1));
 }
+
+#[snippet("rollback_union_find")]
+pub struct RollbackUnionFind {
+    root: Vec<usize>,
+    size: Vec<usize>,
+    history: Vec<Option<(usize, usize)>>,
+}
+
+#[snippet("rollback_union_find")]
+impl RollbackUnionFind {
+    pub fn new(nodes: usize) -> Self {
+        Self {
+            root: (0..=nodes).collect(),
+            size: vec![1; nodes + 1],
+            history: Vec::new(),
+        }
+    }
+
+    pub fn merge(&mut self, a: usize, b: usize) -> bool {
+        let a = self.root_of(a);
+        let b = self.root_of(b);
+
+        if a == b {
+            self.history.push(None);
+            return false;
+        }
+
+        let (a, b) = if self.size[a] < self.size[b] {
+            (a, b)
+        } else {
+            (b, a)
+        };
+
+        self.root[a] = b;
+        self.size[b] += self.size[a];
+        self.history.push(Some((a, b)));
+        true
+    }
+
+    pub fn same_root(&self, a: usize, b: usize) -> bool {
+        self.root_of(a) == self.root_of(b)
+    }
+
+    pub fn root_of(&self, mut a: usize) -> usize {
+        while self.root[a] != a {
+            a = self.root[a];
+        }
+        a
+    }
+
+    pub fn size(&self, a: usize) -> usize {
+        self.size[self.root_of(a)]
+    }
+
+    pub fn undo(&mut self) {
+        if let Some((a, b)) = self.history.pop().expect("nothing to undo") {
+            self.root[a] = a;
+            self.size[b] -= self.size[a];
+        }
+    }
+
+    pub fn snapshot(&self) -> usize {
+        self.history.len()
+    }
+
+    pub fn rollback(&mut self, snapshot: usize) {
+        while self.history.len() > snapshot {
+            self.undo();
+        }
+    }
+}
+
+#[snippet("dynamic_connectivity")]
+use std::collections::BTreeMap;
+
+#[snippet(name = "dynamic_connectivity", include = "rollback_union_find")]
+pub struct DynamicConnectivity {
+    nodes: usize,
+    time: usize,
+    alive: BTreeMap<(usize, usize), Vec<usize>>,
+    intervals: Vec<(usize, usize, usize, usize)>,
+    queries: Vec<(usize, usize, usize)>,
+}
+
+#[snippet("dynamic_connectivity")]
+impl DynamicConnectivity {
+    pub fn new(nodes: usize) -> Self {
+        Self {
+            nodes,
+            time: 0,
+            alive: BTreeMap::new(),
+            intervals: Vec::new(),
+            queries: Vec::new(),
+        }
+    }
+
+    fn edge_key(a: usize, b: usize) -> (usize, usize) {
+        (a.min(b), a.max(b))
+    }
+
+    pub fn add_edge(&mut self, a: usize, b: usize) {
+        self.alive
+            .entry(Self::edge_key(a, b))
+            .or_default()
+            .push(self.time);
+        self.time += 1;
+    }
+
+    pub fn remove_edge(&mut self, a: usize, b: usize) {
+        let key = Self::edge_key(a, b);
+        let begins = self.alive.get_mut(&key).expect("edge does not exist");
+        let begin = begins.pop().unwrap();
+        if begins.is_empty() {
+            self.alive.remove(&key);
+        }
+        self.intervals.push((begin, self.time, key.0, key.1));
+        self.time += 1;
+    }
+
+    pub fn query(&mut self, a: usize, b: usize) -> usize {
+        self.queries.push((self.time, a, b));
+        self.time += 1;
+        self.queries.len() - 1
+    }
+
+    fn insert(
+        tree: &mut [Vec<(usize, usize)>],
+        l: usize,
+        r: usize,
+        edge: (usize, usize),
+        seg_l: usize,
+        seg_r: usize,
+        index: usize,
+    ) {
+        if r <= seg_l || seg_r <= l {
+            return;
+        }
+        if l <= seg_l && seg_r <= r {
+            tree[index].push(edge);
+            return;
+        }
+        let seg_mid = (seg_l + seg_r) / 2;
+        Self::insert(tree, l, r, edge, seg_l, seg_mid, index * 2);
+        Self::insert(tree, l, r, edge, seg_mid, seg_r, index * 2 + 1);
+    }
+
+    pub fn solve(mut self) -> Vec<bool> {
+        let end = self.time;
+        let alive = std::mem::take(&mut self.alive);
+        for ((a, b), begins) in alive {
+            for begin in begins {
+                self.intervals.push((begin, end, a, b));
+            }
+        }
+
+        let padded_len = end.max(1).next_power_of_two();
+        let mut tree = vec![Vec::new(); padded_len * 2];
+        for &(l, r, a, b) in &self.intervals {
+            Self::insert(&mut tree, l, r, (a, b), 0, padded_len, 1);
+        }
+
+        let mut queries_at = vec![None; padded_len];
+        for (i, &(time, _, _)) in self.queries.iter().enumerate() {
+            queries_at[time] = Some(i);
+        }
+
+        let mut answers = vec![false; self.queries.len()];
+        let mut uf = RollbackUnionFind::new(self.nodes);
+        let mut stack = vec![(1, false)];
+
+        while let Some((index, leaving)) = stack.pop() {
+            if leaving {
+                uf.rollback(uf.snapshot() - tree[index].len());
+                continue;
+            }
+
+            for &(a, b) in &tree[index] {
+                uf.merge(a, b);
+            }
+            stack.push((index, true));
+
+            if index < padded_len {
+                stack.push((index * 2 + 1, false));
+                stack.push((index * 2, false));
+            } else if let Some(i) = queries_at[index - padded_len] {
+                let (_, a, b) = self.queries[i];
+                answers[i] = uf.same_root(a, b);
+            }
+        }
+
+        answers
+    }
+}
+
+#[snippet("persistent_union_find")]
+pub struct PersistentUnionFind {
+    root: Vec<usize>,
+    merged_at: Vec<usize>,
+    sizes: Vec<Vec<(usize, usize)>>,
+    now: usize,
+}
+
+#[snippet("persistent_union_find")]
+impl PersistentUnionFind {
+    pub fn new(nodes: usize) -> Self {
+        Self {
+            root: (0..=nodes).collect(),
+            merged_at: vec![usize::MAX; nodes + 1],
+            sizes: vec![vec![(0, 1)]; nodes + 1],
+            now: 0,
+        }
+    }
+
+    pub fn now(&self) -> usize {
+        self.now
+    }
+
+    pub fn merge(&mut self, a: usize, b: usize) -> usize {
+        self.now += 1;
+        let a = self.root_at(a, self.now);
+        let b = self.root_at(b, self.now);
+
+        if a != b {
+            let size_a = self.size_at(a, self.now);
+            let size_b = self.size_at(b, self.now);
+            let (a, b) = if size_a < size_b { (a, b) } else { (b, a) };
+
+            self.root[a] = b;
+            self.merged_at[a] = self.now;
+            self.sizes[b].push((self.now, size_a + size_b));
+        }
+
+        self.now
+    }
+
+    pub fn root_at(&self, mut a: usize, time: usize) -> usize {
+        while self.merged_at[a] <= time {
+            a = self.root[a];
+        }
+        a
+    }
+
+    pub fn same_root_at(&self, a: usize, b: usize, time: usize) -> bool {
+        self.root_at(a, time) == self.root_at(b, time)
+    }
+
+    pub fn size_at(&self, a: usize, time: usize) -> usize {
+        let sizes = &self.sizes[self.root_at(a, time)];
+        let index = sizes.partition_point(|&(t, _)| t <= time);
+        sizes[index - 1].1
+    }
+}
+
+#[test]
+fn test_rollback_union_find() {
+    let mut uf = RollbackUnionFind::new(5);
+    uf.merge(0, 1);
+    let snapshot = uf.snapshot();
+    assert!(uf.merge(2, 3));
+    assert!(uf.merge(1, 3));
+    assert!(!uf.merge(0, 2));
+    assert!(uf.same_root(0, 2));
+    assert_eq!(uf.size(3), 4);
+    uf.undo();
+    assert!(uf.same_root(0, 2));
+    uf.rollback(snapshot);
+    assert!(uf.same_root(0, 1));
+    assert!(!uf.same_root(0, 2));
+    assert!(!uf.same_root(2, 3));
+    assert_eq!(uf.size(0), 2);
+    assert_eq!(uf.size(2), 1);
+}
+
+#[test]
+fn test_dynamic_connectivity() {
+    let mut dc = DynamicConnectivity::new(4);
+    let q0 = dc.query(0, 1);
+    dc.add_edge(0, 1);
+    dc.add_edge(1, 2);
+    let q1 = dc.query(0, 2);
+    dc.add_edge(2, 1);
+    dc.remove_edge(1, 2);
+    let q2 = dc.query(0, 2);
+    dc.remove_edge(2, 1);
+    let q3 = dc.query(0, 2);
+    dc.add_edge(2, 3);
+    dc.add_edge(3, 0);
+    let q4 = dc.query(1, 2);
+    dc.remove_edge(0, 1);
+    let q5 = dc.query(0, 1);
+    let q6 = dc.query(0, 2);
+
+    let answers = dc.solve();
+    assert!(!answers[q0]);
+    assert!(answers[q1]);
+    assert!(answers[q2]);
+    assert!(!answers[q3]);
+    assert!(answers[q4]);
+    assert!(!answers[q5]);
+    assert!(answers[q6]);
+}
+
+#[test]
+fn test_persistent_union_find() {
+    let mut uf = PersistentUnionFind::new(5);
+    assert_eq!(uf.merge(0, 1), 1);
+    assert_eq!(uf.merge(2, 3), 2);
+    assert_eq!(uf.merge(0, 1), 3);
+    assert_eq!(uf.merge(1, 3), 4);
+    assert_eq!(uf.now(), 4);
+
+    assert!(!uf.same_root_at(0, 1, 0));
+    assert!(uf.same_root_at(0, 1, 1));
+    assert!(!uf.same_root_at(0, 3, 3));
+    assert!(uf.same_root_at(0, 3, 4));
+    assert!(uf.same_root_at(1, 2, 10));
+    assert!(!uf.same_root_at(0, 4, 10));
+    assert_eq!(uf.size_at(0, 0), 1);
+    assert_eq!(uf.size_at(0, 3), 2);
+    assert_eq!(uf.size_at(2, 4), 4);
+    assert_eq!(uf.size_at(4, 4), 1);
+}